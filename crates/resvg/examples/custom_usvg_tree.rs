@@ -23,6 +23,7 @@ fn main() {
             units: usvg::Units::ObjectBoundingBox,
             transform: usvg::Transform::default(),
             spread_method: usvg::SpreadMethod::Pad,
+            color_interpolation: usvg::filter::ColorInterpolation::SRGB,
             stops: vec![
                 usvg::Stop {
                     offset: usvg::StopOffset::ZERO,
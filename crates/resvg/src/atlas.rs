@@ -0,0 +1,131 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use usvg::NodeExt;
+
+use crate::geom::{IntRect, IntSize};
+use crate::tree::Tree;
+
+struct Sprite {
+    id: String,
+    tree: Tree,
+    size: IntSize,
+    /// Sprite's bounding box top-left corner after `transform`.
+    origin: tiny_skia::Point,
+}
+
+impl Tree {
+    /// Renders nodes with the specified IDs into separate regions of the pixmap.
+    ///
+    /// Each node is rendered the same way as [`Tree::from_usvg_node`] would do,
+    /// with `transform` applied to it, and is placed using a simple shelf packer.
+    ///
+    /// Returns the ID and the pixmap region of each rendered node, in the `ids` order.
+    /// Nodes that cannot be found, have a zero size or do not fit
+    /// into the pixmap are skipped.
+    pub fn render_atlas(
+        &self,
+        ids: &[&str],
+        transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Vec<(String, IntRect)> {
+        let mut sprites: Vec<Sprite> = ids
+            .iter()
+            .filter_map(|id| self.prepare_sprite(id, transform))
+            .collect();
+
+        // Taller sprites first, so shelves will be filled more densely.
+        let mut order: Vec<usize> = (0..sprites.len()).collect();
+        order.sort_by(|a, b| sprites[*b].size.height().cmp(&sprites[*a].size.height()));
+
+        let mut placements: Vec<Option<IntRect>> = vec![None; sprites.len()];
+        let mut x = 0;
+        let mut shelf_y = 0;
+        let mut shelf_height = 0;
+        for idx in order {
+            let size = sprites[idx].size;
+            if x != 0 && x + size.width() > pixmap.width() {
+                x = 0;
+                shelf_y += shelf_height;
+                shelf_height = 0;
+            }
+
+            if x + size.width() > pixmap.width() || shelf_y + size.height() > pixmap.height() {
+                log::warn!("Node '{}' doesn't fit into the atlas.", sprites[idx].id);
+                continue;
+            }
+
+            placements[idx] = IntRect::new(x as i32, shelf_y as i32, size.width(), size.height());
+            x += size.width();
+            shelf_height = shelf_height.max(size.height());
+        }
+
+        let mut result = Vec::new();
+        for (sprite, rect) in sprites.drain(..).zip(placements) {
+            let rect = match rect {
+                Some(v) => v,
+                None => continue,
+            };
+
+            if render_sprite(&sprite, transform, rect, pixmap).is_some() {
+                result.push((sprite.id, rect));
+            }
+        }
+
+        result
+    }
+
+    fn prepare_sprite(&self, id: &str, transform: tiny_skia::Transform) -> Option<Sprite> {
+        let node = match self.root.descendants().find(|n| &*n.id() == id) {
+            Some(v) => v,
+            None => {
+                log::warn!("Node '{}' is not found.", id);
+                return None;
+            }
+        };
+
+        let tree = Tree::from_usvg_node(&node)?;
+
+        let (w, h) = (tree.size.width() as f32, tree.size.height() as f32);
+        let mut points = [
+            tiny_skia::Point::from_xy(0.0, 0.0),
+            tiny_skia::Point::from_xy(w, 0.0),
+            tiny_skia::Point::from_xy(w, h),
+            tiny_skia::Point::from_xy(0.0, h),
+        ];
+        transform.map_points(&mut points);
+        let bbox = tiny_skia::Rect::from_points(&points)?;
+
+        let size = IntSize::new(bbox.width().ceil() as u32, bbox.height().ceil() as u32)?;
+        Some(Sprite {
+            id: id.to_string(),
+            tree,
+            size,
+            origin: tiny_skia::Point::from_xy(bbox.x(), bbox.y()),
+        })
+    }
+}
+
+fn render_sprite(
+    sprite: &Sprite,
+    transform: tiny_skia::Transform,
+    rect: IntRect,
+    pixmap: &mut tiny_skia::PixmapMut,
+) -> Option<()> {
+    let mut sprite_pixmap = tiny_skia::Pixmap::new(rect.width(), rect.height())?;
+    let ts = tiny_skia::Transform::from_translate(-sprite.origin.x, -sprite.origin.y)
+        .pre_concat(transform);
+    sprite.tree.render(ts, &mut sprite_pixmap.as_mut());
+
+    pixmap.draw_pixmap(
+        rect.x(),
+        rect.y(),
+        sprite_pixmap.as_ref(),
+        &tiny_skia::PixmapPaint::default(),
+        tiny_skia::Transform::identity(),
+        None,
+    );
+
+    Some(())
+}
@@ -0,0 +1,138 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// A drawing target.
+///
+/// All the final drawing operations performed by [`Tree::render_to_canvas`](crate::Tree::render_to_canvas)
+/// go through this trait, which allows replacing or intercepting the rasterizer.
+/// `tiny-skia` is used by default.
+///
+/// Group layers, clip paths, masks, patterns and filters are still rendered
+/// by `tiny-skia` and passed to the canvas as pixmaps.
+///
+/// When set, a mask has the same size as the canvas.
+pub trait Canvas {
+    /// Returns the canvas width in pixels.
+    fn width(&self) -> u32;
+
+    /// Returns the canvas height in pixels.
+    fn height(&self) -> u32;
+
+    /// Fills a path.
+    fn fill_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        fill_rule: tiny_skia::FillRule,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    );
+
+    /// Strokes a path.
+    fn stroke_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        stroke: &tiny_skia::Stroke,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    );
+
+    /// Fills a rectangle.
+    fn fill_rect(
+        &mut self,
+        rect: tiny_skia::Rect,
+        paint: &tiny_skia::Paint,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    );
+
+    /// Draws a pixmap at the specified position.
+    fn draw_pixmap(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: tiny_skia::PixmapRef,
+        paint: &tiny_skia::PixmapPaint,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    );
+}
+
+impl Canvas for tiny_skia::PixmapMut<'_> {
+    fn width(&self) -> u32 {
+        tiny_skia::PixmapMut::width(self)
+    }
+
+    fn height(&self) -> u32 {
+        tiny_skia::PixmapMut::height(self)
+    }
+
+    fn fill_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        fill_rule: tiny_skia::FillRule,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        tiny_skia::PixmapMut::fill_path(self, path, paint, fill_rule, transform, mask);
+    }
+
+    fn stroke_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        stroke: &tiny_skia::Stroke,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        tiny_skia::PixmapMut::stroke_path(self, path, paint, stroke, transform, mask);
+    }
+
+    fn fill_rect(
+        &mut self,
+        rect: tiny_skia::Rect,
+        paint: &tiny_skia::Paint,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        tiny_skia::PixmapMut::fill_rect(self, rect, paint, transform, mask);
+    }
+
+    fn draw_pixmap(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: tiny_skia::PixmapRef,
+        paint: &tiny_skia::PixmapPaint,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        tiny_skia::PixmapMut::draw_pixmap(self, x, y, pixmap, paint, transform, mask);
+    }
+}
+
+pub trait CanvasExt {
+    fn create_rect_mask(
+        &self,
+        transform: tiny_skia::Transform,
+        rect: tiny_skia::Rect,
+    ) -> Option<tiny_skia::Mask>;
+}
+
+impl<T: Canvas + ?Sized> CanvasExt for T {
+    fn create_rect_mask(
+        &self,
+        transform: tiny_skia::Transform,
+        rect: tiny_skia::Rect,
+    ) -> Option<tiny_skia::Mask> {
+        let path = tiny_skia::PathBuilder::from_rect(rect);
+
+        let mut mask = tiny_skia::Mask::new(self.width(), self.height())?;
+        mask.fill_path(&path, tiny_skia::FillRule::Winding, true, transform);
+
+        Some(mask)
+    }
+}
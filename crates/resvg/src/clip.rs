@@ -6,7 +6,6 @@ use std::rc::Rc;
 
 use crate::render::Context;
 use crate::tree::{ConvTransform, Node, OptionLog};
-use crate::IntRect;
 
 pub struct ClipPath {
     pub transform: tiny_skia::Transform,
@@ -36,19 +35,25 @@ pub fn convert(upath: Option<Rc<usvg::ClipPath>>, object_bbox: usvg::PathBbox) -
     })
 }
 
-pub fn apply(clip: &ClipPath, transform: tiny_skia::Transform, pixmap: &mut tiny_skia::Pixmap) {
+pub fn apply(
+    clip: &ClipPath,
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    pixmap: &mut tiny_skia::Pixmap,
+) {
     let mut clip_pixmap = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height()).unwrap();
     clip_pixmap.fill(tiny_skia::Color::BLACK);
 
     draw_children(
         &clip.children,
         tiny_skia::BlendMode::Clear,
+        ctx,
         transform.pre_concat(clip.transform),
         &mut clip_pixmap.as_mut(),
     );
 
     if let Some(ref clip) = clip.clip_path {
-        apply(clip, transform, pixmap);
+        apply(clip, ctx, transform, pixmap);
     }
 
     let mut mask = tiny_skia::Mask::from_pixmap(clip_pixmap.as_ref(), tiny_skia::MaskType::Alpha);
@@ -59,18 +64,14 @@ pub fn apply(clip: &ClipPath, transform: tiny_skia::Transform, pixmap: &mut tiny
 fn draw_children(
     children: &[Node],
     mode: tiny_skia::BlendMode,
+    ctx: &Context,
     transform: tiny_skia::Transform,
     pixmap: &mut tiny_skia::PixmapMut,
 ) {
     for child in children {
         match child {
             Node::FillPath(ref path) => {
-                // We could use any values here. They will not be used anyway.
-                let ctx = Context {
-                    max_bbox: IntRect::new(0, 0, 1, 1).unwrap(),
-                };
-
-                crate::path::render_fill_path(path, mode, &ctx, transform, pixmap);
+                crate::path::render_fill_path(path, mode, ctx, transform, pixmap);
             }
             Node::Group(ref group) => {
                 let transform = transform.pre_concat(group.transform);
@@ -79,9 +80,9 @@ fn draw_children(
                     // If a `clipPath` child also has a `clip-path`
                     // then we should render this child on a new canvas,
                     // clip it, and only then draw it to the `clipPath`.
-                    clip_group(&group.children, clip, transform, pixmap);
+                    clip_group(&group.children, clip, ctx, transform, pixmap);
                 } else {
-                    draw_children(&group.children, mode, transform, pixmap);
+                    draw_children(&group.children, mode, ctx, transform, pixmap);
                 }
             }
             _ => {}
@@ -92,6 +93,7 @@ fn draw_children(
 fn clip_group(
     children: &[Node],
     clip: &ClipPath,
+    ctx: &Context,
     transform: tiny_skia::Transform,
     pixmap: &mut tiny_skia::PixmapMut,
 ) -> Option<()> {
@@ -100,10 +102,11 @@ fn clip_group(
     draw_children(
         children,
         tiny_skia::BlendMode::SourceOver,
+        ctx,
         transform,
         &mut clip_pixmap.as_mut(),
     );
-    apply(clip, transform, &mut clip_pixmap);
+    apply(clip, ctx, transform, &mut clip_pixmap);
 
     let mut paint = tiny_skia::PixmapPaint::default();
     paint.blend_mode = tiny_skia::BlendMode::Xor;
@@ -0,0 +1,139 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use usvg::NodeExt;
+
+use crate::Tree;
+
+/// An SVG feature that cannot be rendered as specified.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Unsupported {
+    /// A text element that wasn't converted into paths.
+    ///
+    /// Such elements are skipped.
+    Text {
+        /// Element's ID. Can be empty.
+        id: String,
+    },
+    /// A raster image that cannot be decoded.
+    ///
+    /// Such images are skipped or filled with
+    /// [`RenderOptions::broken_image_fill`](crate::RenderOptions::broken_image_fill).
+    /// All raster images are reported when the `raster-images` build feature is disabled.
+    UndecodableImage {
+        /// Element's ID or a filter ID, when the image is referenced by `feImage`. Can be empty.
+        id: String,
+    },
+    /// A filter primitive that uses the `BackgroundImage` or `BackgroundAlpha` input.
+    ///
+    /// `enable-background` is not supported, therefore
+    /// `SourceGraphic` and `SourceAlpha` are used instead.
+    BackgroundInput {
+        /// Filter's ID.
+        filter_id: String,
+    },
+}
+
+impl Tree {
+    /// Lists features that will be skipped or approximated during rendering.
+    ///
+    /// Includes the content of clip paths, masks, patterns and filters.
+    /// Each issue is reported once.
+    ///
+    /// Raster images are decoded to be validated, which can be slow.
+    /// Images that cannot be loaded at all are removed during parsing and are not reported.
+    pub fn compatibility_report(&self) -> Vec<Unsupported> {
+        let mut report = Vec::new();
+        check_node(&self.root, &mut report);
+        report
+    }
+}
+
+fn check_node(root: &usvg::Node, report: &mut Vec<Unsupported>) {
+    for node in root.descendants() {
+        match *node.borrow() {
+            usvg::NodeKind::Group(ref group) => {
+                for filter in &group.filters {
+                    check_filter(filter, report);
+                }
+            }
+            usvg::NodeKind::Image(ref image) => {
+                if !is_decodable(&image.kind) {
+                    push(
+                        report,
+                        Unsupported::UndecodableImage {
+                            id: image.id.clone(),
+                        },
+                    );
+                }
+            }
+            usvg::NodeKind::Text(ref text) => {
+                push(
+                    report,
+                    Unsupported::Text {
+                        id: text.id.clone(),
+                    },
+                );
+            }
+            usvg::NodeKind::Path(_) => {}
+        }
+
+        node.subroots(|subroot| check_node(&subroot, report));
+    }
+}
+
+fn check_filter(filter: &usvg::filter::Filter, report: &mut Vec<Unsupported>) {
+    for primitive in &filter.primitives {
+        if primitive
+            .kind
+            .has_input(&usvg::filter::Input::BackgroundImage)
+            || primitive
+                .kind
+                .has_input(&usvg::filter::Input::BackgroundAlpha)
+        {
+            push(
+                report,
+                Unsupported::BackgroundInput {
+                    filter_id: filter.id.clone(),
+                },
+            );
+        }
+
+        if let usvg::filter::Kind::Image(ref fe) = primitive.kind {
+            if let usvg::filter::ImageKind::Image(ref kind) = fe.data {
+                if !is_decodable(kind) {
+                    push(
+                        report,
+                        Unsupported::UndecodableImage {
+                            id: filter.id.clone(),
+                        },
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn is_decodable(kind: &usvg::ImageKind) -> bool {
+    if let usvg::ImageKind::SVG(_) = kind {
+        return true;
+    }
+
+    #[cfg(feature = "raster-images")]
+    {
+        crate::image::decode_raster(kind).is_some()
+    }
+
+    #[cfg(not(feature = "raster-images"))]
+    {
+        false
+    }
+}
+
+fn push(report: &mut Vec<Unsupported>, item: Unsupported) {
+    // Shared elements, like patterns, are visited once per reference.
+    if !report.contains(&item) {
+        report.push(item);
+    }
+}
@@ -0,0 +1,359 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::rc::Rc;
+
+use crate::paint_server::Paint;
+use crate::path::StrokePath;
+use crate::render::Context;
+use crate::tree::Node;
+use crate::DebugBbox;
+
+const NODE_COLOR: (u8, u8, u8) = (255, 0, 0);
+const FILTER_COLOR: (u8, u8, u8) = (0, 0, 255);
+const CLIP_COLOR: (u8, u8, u8) = (0, 128, 0);
+
+/// Strokes bounding boxes of nodes on top of an already rendered pixmap.
+pub fn draw_bboxes(
+    children: &[Node],
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    pixmap: &mut dyn crate::Canvas,
+) {
+    for node in children {
+        match node {
+            Node::Group(ref group) => {
+                let transform = transform.pre_concat(group.transform);
+                draw_bboxes(&group.children, ctx, transform, pixmap);
+
+                if ctx.opt.draw_bboxes != DebugBbox::All {
+                    continue;
+                }
+
+                for filter in &group.filters {
+                    let r = filter.region;
+                    if let Some(rect) = tiny_skia::Rect::from_xywh(
+                        r.x() as f32,
+                        r.y() as f32,
+                        r.width() as f32,
+                        r.height() as f32,
+                    ) {
+                        draw_rect(rect, transform, FILTER_COLOR, ctx, pixmap);
+                    }
+                }
+
+                if let Some(ref clip) = group.clip_path {
+                    let transform = transform.pre_concat(clip.transform);
+                    if let Some(rect) = nodes_bbox(&clip.children, transform) {
+                        draw_rect(
+                            rect,
+                            tiny_skia::Transform::identity(),
+                            CLIP_COLOR,
+                            ctx,
+                            pixmap,
+                        );
+                    }
+                }
+            }
+            Node::FillPath(_) | Node::StrokePath(_) | Node::Image(_) | Node::TextPlaceholder(_) => {
+                if let Some(rect) = node_bbox(node, transform) {
+                    draw_rect(
+                        rect,
+                        tiny_skia::Transform::identity(),
+                        NODE_COLOR,
+                        ctx,
+                        pixmap,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns node's geometry bounding box in canvas coordinates.
+fn node_bbox(node: &Node, transform: tiny_skia::Transform) -> Option<tiny_skia::Rect> {
+    match node {
+        Node::Group(ref group) => {
+            nodes_bbox(&group.children, transform.pre_concat(group.transform))
+        }
+        Node::FillPath(ref path) => {
+            transformed_path_bbox(&path.path, transform.pre_concat(path.transform))
+        }
+        Node::StrokePath(ref path) => {
+            transformed_path_bbox(&path.path, transform.pre_concat(path.transform))
+        }
+        Node::Image(ref image) => {
+            let r = image.view_box.rect;
+            let rect = tiny_skia::Rect::from_xywh(
+                r.x() as f32,
+                r.y() as f32,
+                r.width() as f32,
+                r.height() as f32,
+            )?;
+            let path = tiny_skia::PathBuilder::from_rect(rect);
+            transformed_path_bbox(&path, transform.pre_concat(image.transform))
+        }
+        Node::TextPlaceholder(ref placeholder) => {
+            let path = tiny_skia::PathBuilder::from_rect(placeholder.rect);
+            transformed_path_bbox(&path, transform.pre_concat(placeholder.transform))
+        }
+    }
+}
+
+fn nodes_bbox(children: &[Node], transform: tiny_skia::Transform) -> Option<tiny_skia::Rect> {
+    let mut bbox: Option<tiny_skia::Rect> = None;
+    for rect in children
+        .iter()
+        .filter_map(|node| node_bbox(node, transform))
+    {
+        bbox = match bbox {
+            Some(b) => tiny_skia::Rect::from_ltrb(
+                b.left().min(rect.left()),
+                b.top().min(rect.top()),
+                b.right().max(rect.right()),
+                b.bottom().max(rect.bottom()),
+            ),
+            None => Some(rect),
+        };
+    }
+
+    bbox
+}
+
+fn transformed_path_bbox(
+    path: &tiny_skia::Path,
+    transform: tiny_skia::Transform,
+) -> Option<tiny_skia::Rect> {
+    Some(path.clone().transform(transform)?.bounds())
+}
+
+fn draw_rect(
+    rect: tiny_skia::Rect,
+    transform: tiny_skia::Transform,
+    color: (u8, u8, u8),
+    ctx: &Context,
+    pixmap: &mut dyn crate::Canvas,
+) {
+    let path = StrokePath {
+        id: String::new(),
+        transform: tiny_skia::Transform::identity(),
+        paint: Paint::Shader(tiny_skia::Shader::SolidColor(tiny_skia::Color::from_rgba8(
+            color.0, color.1, color.2, 255,
+        ))),
+        stroke: tiny_skia::Stroke::default(),
+        anti_alias: true,
+        text_baseline: None,
+        path: Rc::new(tiny_skia::PathBuilder::from_rect(rect)),
+    };
+
+    crate::path::render_stroke_path(
+        &path,
+        tiny_skia::BlendMode::SourceOver,
+        ctx,
+        transform,
+        pixmap,
+    );
+}
+
+impl crate::Tree {
+    /// Returns a pseudo-XML representation of the rendering tree.
+    ///
+    /// Unlike the usvg output, shows the tree the way resvg renders it:
+    /// converted paint servers, split fill and stroke paths, and whether a group
+    /// requires a separate layer or only forwards its transform.
+    ///
+    /// Bounding boxes are in the root user space and include transforms.
+    /// The output format is unstable and intended only for debugging.
+    pub fn debug_dump(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "<tree size=\"{} {}\" view-box=\"{}\">\n",
+            self.size.width(),
+            self.size.height(),
+            fmt_rect(
+                self.view_box.rect.x() as f32,
+                self.view_box.rect.y() as f32,
+                self.view_box.rect.width() as f32,
+                self.view_box.rect.height() as f32,
+            ),
+        ));
+        dump_nodes(
+            &self.children,
+            tiny_skia::Transform::identity(),
+            1,
+            &mut out,
+        );
+        out.push_str("</tree>\n");
+        out
+    }
+}
+
+fn dump_nodes(children: &[Node], transform: tiny_skia::Transform, depth: usize, out: &mut String) {
+    for node in children {
+        dump_node(node, transform, depth, out);
+    }
+}
+
+fn dump_node(node: &Node, transform: tiny_skia::Transform, depth: usize, out: &mut String) {
+    let indent = "    ".repeat(depth);
+
+    let mut attrs = String::new();
+    let node_transform = match node {
+        Node::Group(ref group) => group.transform,
+        Node::FillPath(ref path) => path.transform,
+        Node::StrokePath(ref path) => path.transform,
+        Node::Image(ref image) => image.transform,
+        Node::TextPlaceholder(ref placeholder) => placeholder.transform,
+    };
+    if !node_transform.is_identity() {
+        attrs.push_str(&format!(" transform=\"{}\"", fmt_transform(node_transform)));
+    }
+    if let Some(r) = node_bbox(node, transform) {
+        let bbox = fmt_rect(r.x(), r.y(), r.width(), r.height());
+        attrs.push_str(&format!(" bbox=\"{}\"", bbox));
+    }
+
+    match node {
+        Node::Group(ref group) => {
+            attrs.push_str(&format!(" layer=\"{}\"", !group.is_transform_only()));
+            if group.opacity != 1.0 {
+                attrs.push_str(&format!(" opacity=\"{}\"", group.opacity));
+            }
+            if group.blend_mode != tiny_skia::BlendMode::SourceOver {
+                attrs.push_str(&format!(" blend-mode=\"{:?}\"", group.blend_mode));
+            }
+            if group.isolate {
+                attrs.push_str(" isolate=\"true\"");
+            }
+            if !group.filters.is_empty() {
+                attrs.push_str(&format!(" filters=\"{}\"", group.filters.len()));
+            }
+
+            out.push_str(&format!("{}<group{}>\n", indent, attrs));
+
+            let transform = transform.pre_concat(group.transform);
+            if let Some(ref clip) = group.clip_path {
+                dump_clip_path(clip, transform, depth + 1, out);
+            }
+            if let Some(ref mask) = group.mask {
+                dump_mask(mask, transform, depth + 1, out);
+            }
+            dump_nodes(&group.children, transform, depth + 1, out);
+
+            out.push_str(&format!("{}</group>\n", indent));
+        }
+        Node::FillPath(ref path) => {
+            let rule = match path.rule {
+                tiny_skia::FillRule::Winding => "nonzero",
+                tiny_skia::FillRule::EvenOdd => "evenodd",
+            };
+            out.push_str(&format!(
+                "{}<fill-path{} paint=\"{}\" rule=\"{}\" anti-alias=\"{}\"/>\n",
+                indent,
+                attrs,
+                fmt_paint(&path.paint),
+                rule,
+                path.anti_alias
+            ));
+        }
+        Node::StrokePath(ref path) => {
+            out.push_str(&format!(
+                "{}<stroke-path{} paint=\"{}\" width=\"{}\" anti-alias=\"{}\"/>\n",
+                indent,
+                attrs,
+                fmt_paint(&path.paint),
+                path.stroke.width,
+                path.anti_alias
+            ));
+        }
+        Node::Image(ref image) => {
+            let kind = match image.kind {
+                #[cfg(feature = "raster-images")]
+                crate::image::ImageKind::Raster(ref raster) => {
+                    format!("raster {}x{}", raster.width(), raster.height())
+                }
+                crate::image::ImageKind::Vector(_) => "vector".to_string(),
+                crate::image::ImageKind::Broken => "broken".to_string(),
+            };
+            out.push_str(&format!("{}<image{} kind=\"{}\"/>\n", indent, attrs, kind));
+        }
+        Node::TextPlaceholder(_) => {
+            out.push_str(&format!("{}<text-placeholder{}/>\n", indent, attrs));
+        }
+    }
+}
+
+fn dump_clip_path(
+    clip: &crate::clip::ClipPath,
+    transform: tiny_skia::Transform,
+    depth: usize,
+    out: &mut String,
+) {
+    let indent = "    ".repeat(depth);
+    out.push_str(&format!(
+        "{}<clip-path transform=\"{}\">\n",
+        indent,
+        fmt_transform(clip.transform)
+    ));
+
+    let transform = transform.pre_concat(clip.transform);
+    if let Some(ref clip) = clip.clip_path {
+        dump_clip_path(clip, transform, depth + 1, out);
+    }
+    dump_nodes(&clip.children, transform, depth + 1, out);
+
+    out.push_str(&format!("{}</clip-path>\n", indent));
+}
+
+fn dump_mask(
+    mask: &crate::mask::Mask,
+    transform: tiny_skia::Transform,
+    depth: usize,
+    out: &mut String,
+) {
+    let indent = "    ".repeat(depth);
+    let r = mask.region;
+    out.push_str(&format!(
+        "{}<mask region=\"{}\" kind=\"{:?}\">\n",
+        indent,
+        fmt_rect(r.x(), r.y(), r.width(), r.height()),
+        mask.kind
+    ));
+
+    if let Some(ref mask) = mask.mask {
+        dump_mask(mask, transform, depth + 1, out);
+    }
+    dump_nodes(
+        &mask.children,
+        transform.pre_concat(mask.content_transform),
+        depth + 1,
+        out,
+    );
+
+    out.push_str(&format!("{}</mask>\n", indent));
+}
+
+fn fmt_paint(paint: &Paint) -> String {
+    match paint {
+        Paint::Shader(tiny_skia::Shader::SolidColor(c)) => {
+            let c = c.to_color_u8();
+            format!("rgba({} {} {} {})", c.red(), c.green(), c.blue(), c.alpha())
+        }
+        Paint::Shader(tiny_skia::Shader::LinearGradient(_)) => "linear-gradient".to_string(),
+        Paint::Shader(tiny_skia::Shader::RadialGradient(_)) => "radial-gradient".to_string(),
+        Paint::Shader(tiny_skia::Shader::Pattern(_)) => "image".to_string(),
+        Paint::Pattern(ref pattern) => format!("pattern({} children)", pattern.children.len()),
+    }
+}
+
+fn fmt_transform(ts: tiny_skia::Transform) -> String {
+    format!(
+        "{} {} {} {} {} {}",
+        ts.sx, ts.ky, ts.kx, ts.sy, ts.tx, ts.ty
+    )
+}
+
+fn fmt_rect(x: f32, y: f32, width: f32, height: f32) -> String {
+    format!("{} {} {} {}", x, y, width, height)
+}
@@ -0,0 +1,110 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/// A window radius used to reconstruct smooth color transitions.
+///
+/// Bands wider than the window are dithered only near their edges.
+const RADIUS: usize = 8;
+
+/// A normalized 8x8 Bayer matrix.
+const BAYER: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// Applies ordered dithering to smooth color transitions.
+///
+/// An already rendered image has no information about the precision lost
+/// during quantization, therefore it is estimated using a local average.
+/// Only areas where neighbouring values differ by at most 1 are affected,
+/// which leaves flat areas and shape edges intact.
+pub fn apply(pixmap: &mut tiny_skia::PixmapMut) {
+    let width = pixmap.width() as usize;
+    let height = pixmap.height() as usize;
+    let data = pixmap.data_mut();
+
+    for channel in 0..3 {
+        let values: Vec<u8> = data
+            .chunks_exact(tiny_skia::BYTES_PER_PIXEL)
+            .map(|p| p[channel])
+            .collect();
+
+        let (min, max) = window_min_max(&values, width, height);
+        let sums = integral_image(&values, width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let idx = y * width + x;
+                if max[idx] - min[idx] != 1 {
+                    continue;
+                }
+
+                let x0 = x.saturating_sub(RADIUS);
+                let y0 = y.saturating_sub(RADIUS);
+                let x1 = (x + RADIUS + 1).min(width);
+                let y1 = (y + RADIUS + 1).min(height);
+                let sum = sums[y1 * (width + 1) + x1] + sums[y0 * (width + 1) + x0]
+                    - sums[y0 * (width + 1) + x1]
+                    - sums[y1 * (width + 1) + x0];
+                let avg = sum as f32 / ((x1 - x0) * (y1 - y0)) as f32;
+
+                let threshold = (BAYER[y % 8][x % 8] as f32 + 0.5) / 64.0;
+                let c = (avg + threshold)
+                    .floor()
+                    .clamp(min[idx] as f32, max[idx] as f32) as u8;
+
+                let p = &mut data[idx * tiny_skia::BYTES_PER_PIXEL..];
+                // Keep the pixel premultiplied.
+                p[channel] = c.min(p[3]);
+            }
+        }
+    }
+}
+
+/// Returns the minimum and the maximum value around each pixel.
+fn window_min_max(values: &[u8], width: usize, height: usize) -> (Vec<u8>, Vec<u8>) {
+    let mut row_min = vec![0; values.len()];
+    let mut row_max = vec![0; values.len()];
+    for y in 0..height {
+        let row = &values[y * width..(y + 1) * width];
+        for x in 0..width {
+            let window = &row[x.saturating_sub(RADIUS)..(x + RADIUS + 1).min(width)];
+            row_min[y * width + x] = *window.iter().min().unwrap();
+            row_max[y * width + x] = *window.iter().max().unwrap();
+        }
+    }
+
+    let mut min = vec![0; values.len()];
+    let mut max = vec![0; values.len()];
+    for x in 0..width {
+        for y in 0..height {
+            let rows = y.saturating_sub(RADIUS)..(y + RADIUS + 1).min(height);
+            min[y * width + x] = rows.clone().map(|y| row_min[y * width + x]).min().unwrap();
+            max[y * width + x] = rows.map(|y| row_max[y * width + x]).max().unwrap();
+        }
+    }
+
+    (min, max)
+}
+
+/// Returns a summed-area table with an extra zero row and column.
+fn integral_image(values: &[u8], width: usize, height: usize) -> Vec<u32> {
+    let stride = width + 1;
+    let mut sums = vec![0u32; stride * (height + 1)];
+    for y in 0..height {
+        let mut row_sum = 0;
+        for x in 0..width {
+            row_sum += values[y * width + x] as u32;
+            sums[(y + 1) * stride + x + 1] = sums[y * stride + x + 1] + row_sum;
+        }
+    }
+
+    sums
+}
@@ -0,0 +1,66 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::geom::IntSize;
+use crate::render::TextureFormat;
+use crate::tree::Tree;
+
+impl Tree {
+    /// Renders the tree and encodes the result as a lossy WebP image.
+    ///
+    /// `transform` will be used as a root transform.
+    /// `quality` is in a 0..=100 range.
+    ///
+    /// Returns `None` when a pixmap cannot be allocated or encoding has failed.
+    /// WebP images cannot be larger than 16383x16383 pixels.
+    #[cfg(feature = "encode-webp")]
+    pub fn encode_webp(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+        quality: f32,
+    ) -> Option<Vec<u8>> {
+        // Encoders expect a straight alpha.
+        let data = self.render_to_texture(transform, size, TextureFormat::Rgba8)?;
+        let encoder = webp::Encoder::from_rgba(&data, size.width(), size.height());
+        let encoded = encoder
+            .encode_simple(false, quality.clamp(0.0, 100.0))
+            .map_err(|e| log::warn!("Failed to encode a WebP image: {:?}.", e))
+            .ok()?;
+
+        Some(encoded.to_vec())
+    }
+
+    /// Renders the tree and encodes the result as an AVIF image.
+    ///
+    /// `transform` will be used as a root transform.
+    /// `quality` is in a 1..=100 range.
+    ///
+    /// Returns `None` when a pixmap cannot be allocated or encoding has failed.
+    #[cfg(feature = "encode-avif")]
+    pub fn encode_avif(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+        quality: f32,
+    ) -> Option<Vec<u8>> {
+        use rgb::FromSlice;
+
+        // Encoders expect a straight alpha.
+        let data = self.render_to_texture(transform, size, TextureFormat::Rgba8)?;
+        let img = ravif::Img::new(
+            data.as_rgba(),
+            size.width() as usize,
+            size.height() as usize,
+        );
+
+        let encoded = ravif::Encoder::new()
+            .with_quality(quality.clamp(1.0, 100.0))
+            .encode_rgba(img)
+            .map_err(|e| log::warn!("Failed to encode an AVIF image: {}.", e))
+            .ok()?;
+
+        Some(encoded.avif_file)
+    }
+}
@@ -8,6 +8,7 @@ use rgb::FromSlice;
 use usvg::{FuzzyEq, FuzzyZero, Transform};
 
 use crate::geom::{IntRect, UsvgRectExt};
+use crate::render::Context;
 use crate::tree::{ConvTransform, Node};
 
 // TODO: apply single primitive filters in-place
@@ -120,6 +121,8 @@ pub struct Primitive {
 }
 
 pub struct Filter {
+    #[cfg(feature = "profiling")]
+    pub id: String,
     pub region: usvg::Rect,
     pub primitives: Vec<Primitive>,
 }
@@ -172,7 +175,12 @@ fn convert_filter(
         }
     }
 
-    Some(Filter { region, primitives })
+    Some(Filter {
+        #[cfg(feature = "profiling")]
+        id: ufilter.id.clone(),
+        region,
+        primitives,
+    })
 }
 
 fn convert_primitive(
@@ -215,6 +223,7 @@ fn convert_primitive(
                     input: fe.input.clone(),
                     std_dev_x: usvg::PositiveF64::new(std_dev_x).unwrap_or_default(),
                     std_dev_y: usvg::PositiveF64::new(std_dev_y).unwrap_or_default(),
+                    edge_mode: fe.edge_mode,
                 },
             ))
         }
@@ -252,6 +261,7 @@ trait PixmapExt: Sized {
     fn clear(&mut self);
     fn into_srgb(&mut self);
     fn into_linear_rgb(&mut self);
+    fn demultiplied<F: FnOnce(&mut Self)>(&mut self, f: F);
 }
 
 impl PixmapExt for tiny_skia::Pixmap {
@@ -260,9 +270,7 @@ impl PixmapExt for tiny_skia::Pixmap {
     }
 
     fn copy_region(&self, region: IntRect) -> Result<tiny_skia::Pixmap, Error> {
-        let rect =
-            tiny_skia::IntRect::from_xywh(region.x(), region.y(), region.width(), region.height())
-                .ok_or(Error::InvalidRegion)?;
+        let rect = region.to_skia().ok_or(Error::InvalidRegion)?;
         self.clone_rect(rect).ok_or(Error::InvalidRegion)
     }
 
@@ -271,14 +279,21 @@ impl PixmapExt for tiny_skia::Pixmap {
     }
 
     fn into_srgb(&mut self) {
-        svgfilters::demultiply_alpha(self.data_mut().as_rgba_mut());
-        svgfilters::from_linear_rgb(self.data_mut().as_rgba_mut());
-        svgfilters::multiply_alpha(self.data_mut().as_rgba_mut());
+        self.demultiplied(|pixmap| svgfilters::from_linear_rgb(pixmap.data_mut().as_rgba_mut()));
     }
 
     fn into_linear_rgb(&mut self) {
+        self.demultiplied(|pixmap| svgfilters::into_linear_rgb(pixmap.data_mut().as_rgba_mut()));
+    }
+
+    /// Runs `f` on a pixmap with a straight alpha.
+    ///
+    /// Filter primitive results are always premultiplied. Primitives that are defined
+    /// on straight colors should use this method instead of converting manually,
+    /// so the result will be premultiplied again.
+    fn demultiplied<F: FnOnce(&mut Self)>(&mut self, f: F) {
         svgfilters::demultiply_alpha(self.data_mut().as_rgba_mut());
-        svgfilters::into_linear_rgb(self.data_mut().as_rgba_mut());
+        f(self);
         svgfilters::multiply_alpha(self.data_mut().as_rgba_mut());
     }
 }
@@ -370,6 +385,7 @@ struct FilterResult {
 
 pub fn apply(
     filter: &Filter,
+    ctx: &Context,
     ts: tiny_skia::Transform,
     fill_paint: Option<&tiny_skia::Pixmap>,
     stroke_paint: Option<&tiny_skia::Pixmap>,
@@ -383,7 +399,7 @@ pub fn apply(
 
     let ts = usvg::Transform::from_native(ts);
 
-    let result = apply_inner(filter, &inputs, &ts);
+    let result = apply_inner(filter, ctx, &inputs, &ts);
     let result = result.and_then(|image| apply_to_canvas(image, source));
 
     // Clear on error.
@@ -402,6 +418,7 @@ pub fn apply(
 
 fn apply_inner(
     filter: &Filter,
+    ctx: &Context,
     inputs: &FilterInputs,
     ts: &usvg::Transform,
 ) -> Result<Image, Error> {
@@ -413,7 +430,11 @@ fn apply_inner(
         .map(|r| r.to_int_rect_round_out())
         .ok_or(Error::InvalidRegion)?;
 
-    for primitive in &filter.primitives {
+    for (i, primitive) in filter.primitives.iter().enumerate() {
+        if ctx.opt.low_memory {
+            drop_unused_results(&mut results, &filter.primitives[i..]);
+        }
+
         let cs = primitive.color_interpolation;
         let mut subregion = primitive
             .region
@@ -459,7 +480,7 @@ fn apply_inner(
                 let input = get_input(&fe.input, region, inputs, &results)?;
                 apply_tile(input, region)
             }
-            usvg::filter::Kind::Image(ref fe) => apply_image(fe, region, subregion, ts),
+            usvg::filter::Kind::Image(ref fe) => apply_image(fe, ctx, region, subregion, ts),
             usvg::filter::Kind::ComponentTransfer(ref fe) => {
                 let input = get_input(&fe.input, region, inputs, &results)?;
                 apply_component_transfer(fe, cs, input)
@@ -481,7 +502,7 @@ fn apply_inner(
                 let input2 = get_input(&fe.input2, region, inputs, &results)?;
                 apply_displacement_map(fe, region, cs, ts, input1, input2)
             }
-            usvg::filter::Kind::Turbulence(ref fe) => apply_turbulence(fe, region, cs, ts),
+            usvg::filter::Kind::Turbulence(ref fe) => apply_turbulence(fe, ctx, region, cs, ts),
             usvg::filter::Kind::DiffuseLighting(ref fe) => {
                 let input = get_input(&fe.input, region, inputs, &results)?;
                 apply_diffuse_lighting(fe, region, cs, ts, input)
@@ -557,6 +578,36 @@ fn apply_inner(
     }
 }
 
+/// Removes results that are not referenced by the remaining primitives.
+fn drop_unused_results(results: &mut Vec<FilterResult>, remaining: &[Primitive]) {
+    let is_referenced = |name: &str| {
+        let input = usvg::filter::Input::Reference(name.to_string());
+        for primitive in remaining {
+            if primitive.kind.has_input(&input) {
+                return true;
+            }
+
+            // Overwritten by a new result.
+            if primitive.result == name {
+                return false;
+            }
+        }
+
+        false
+    };
+
+    let mut idx = 0;
+    while idx < results.len() {
+        let name = &results[idx].name;
+        let is_shadowed = results[idx + 1..].iter().any(|r| r.name == *name);
+        if is_shadowed || !is_referenced(name) {
+            results.remove(idx);
+        } else {
+            idx += 1;
+        }
+    }
+}
+
 // TODO: merge with mask region logic
 fn calc_region(
     filter: &usvg::filter::Filter,
@@ -694,6 +745,8 @@ fn get_input(
             let image = inputs.source.clone();
             convert_alpha(image)
         }
+        // `enable-background` is not supported, therefore there is no accumulated backdrop.
+        // Browsers do the same.
         usvg::filter::Input::BackgroundImage => {
             get_input(&usvg::filter::Input::SourceGraphic, region, inputs, results)
         }
@@ -725,14 +778,15 @@ fn apply_drop_shadow(
     let mut shadow_pixmap = input_pixmap.clone();
 
     let (sx, sy) = ts.get_scale();
-    if let Some((std_dx, std_dy, box_blur)) =
+    if let Some((std_dx, std_dy)) =
         resolve_std_dev(fe.std_dev_x.get() * sx, fe.std_dev_y.get() * sy)
     {
-        if box_blur {
-            svgfilters::box_blur(std_dx, std_dy, into_svgfilters_image_mut!(shadow_pixmap));
-        } else {
-            svgfilters::iir_blur(std_dx, std_dy, into_svgfilters_image_mut!(shadow_pixmap));
-        }
+        gaussian_blur(
+            std_dx,
+            std_dy,
+            usvg::filter::EdgeMode::None,
+            &mut shadow_pixmap,
+        );
     }
 
     // flood
@@ -753,13 +807,12 @@ fn apply_drop_shadow(
         usvg::filter::ColorInterpolation::LinearRGB => shadow_pixmap.into_linear_rgb(),
     }
 
-    pixmap.draw_pixmap(
-        (fe.dx * sx) as i32,
-        (fe.dy * sy) as i32,
-        shadow_pixmap.as_ref(),
-        &tiny_skia::PixmapPaint::default(),
-        tiny_skia::Transform::identity(),
-        None,
+    let (offset_sx, offset_sy) = signed_scale(ts);
+    draw_shifted_pixmap(
+        &shadow_pixmap,
+        fe.dx * offset_sx,
+        fe.dy * offset_sy,
+        &mut pixmap,
     );
 
     pixmap.draw_pixmap(
@@ -781,19 +834,13 @@ fn apply_blur(
     input: Image,
 ) -> Result<Image, Error> {
     let (sx, sy) = ts.get_scale();
-    let (std_dx, std_dy, box_blur) =
-        match resolve_std_dev(fe.std_dev_x.get() * sx, fe.std_dev_y.get() * sy) {
-            Some(v) => v,
-            None => return Ok(input),
-        };
+    let (std_dx, std_dy) = match resolve_std_dev(fe.std_dev_x.get() * sx, fe.std_dev_y.get() * sy) {
+        Some(v) => v,
+        None => return Ok(input),
+    };
 
     let mut pixmap = input.into_color_space(cs)?.take()?;
-
-    if box_blur {
-        svgfilters::box_blur(std_dx, std_dy, into_svgfilters_image_mut!(pixmap));
-    } else {
-        svgfilters::iir_blur(std_dx, std_dy, into_svgfilters_image_mut!(pixmap));
-    }
+    gaussian_blur(std_dx, std_dy, fe.edge_mode, &mut pixmap);
 
     Ok(Image::from_image(pixmap, cs))
 }
@@ -803,7 +850,7 @@ fn apply_offset(
     ts: &usvg::Transform,
     input: Image,
 ) -> Result<Image, Error> {
-    let (sx, sy) = ts.get_scale();
+    let (sx, sy) = signed_scale(ts);
     let dx = fe.dx * sx;
     let dy = fe.dy * sy;
 
@@ -812,18 +859,49 @@ fn apply_offset(
     }
 
     let mut pixmap = tiny_skia::Pixmap::try_create(input.width(), input.height())?;
-    pixmap.draw_pixmap(
-        dx as i32,
-        dy as i32,
-        input.as_ref().as_ref(),
-        &tiny_skia::PixmapPaint::default(),
-        tiny_skia::Transform::identity(),
-        None,
-    );
+    draw_shifted_pixmap(input.as_ref(), dx, dy, &mut pixmap);
 
     Ok(Image::from_image(pixmap, input.color_space))
 }
 
+/// Returns transform's scale, negated along mirrored axes.
+///
+/// Filters are applied in canvas coordinates, therefore directional values,
+/// like offsets, must be flipped when the canvas is mirrored.
+fn signed_scale(ts: &usvg::Transform) -> (f64, f64) {
+    let (sx, sy) = ts.get_scale();
+    (sx.copysign(ts.a), sy.copysign(ts.d))
+}
+
+/// Draws a pixmap shifted by the specified offset.
+///
+/// Integer offsets are a simple copy, while fractional ones are resampled
+/// using bilinear filtering, so the result is not snapped to the pixel grid.
+fn draw_shifted_pixmap(src: &tiny_skia::Pixmap, dx: f64, dy: f64, dst: &mut tiny_skia::Pixmap) {
+    if dx.fract().is_fuzzy_zero() && dy.fract().is_fuzzy_zero() {
+        dst.draw_pixmap(
+            dx.round() as i32,
+            dy.round() as i32,
+            src.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            tiny_skia::Transform::identity(),
+            None,
+        );
+    } else {
+        dst.draw_pixmap(
+            0,
+            0,
+            src.as_ref(),
+            &tiny_skia::PixmapPaint {
+                quality: tiny_skia::FilterQuality::Bilinear,
+                ..tiny_skia::PixmapPaint::default()
+            },
+            tiny_skia::Transform::from_translate(dx as f32, dy as f32),
+            None,
+        );
+    }
+}
+
 fn apply_blend(
     fe: &usvg::filter::Blend,
     cs: usvg::filter::ColorInterpolation,
@@ -992,6 +1070,7 @@ fn apply_tile(input: Image, region: IntRect) -> Result<Image, Error> {
 
 fn apply_image(
     fe: &usvg::filter::Image,
+    ctx: &Context,
     region: IntRect,
     subregion: IntRect,
     ts: &usvg::Transform,
@@ -1021,7 +1100,7 @@ fn apply_image(
             let mut children = Vec::new();
             crate::image::convert(&uimage, &mut children);
             if let Some(Node::Image(image)) = children.first() {
-                crate::image::render_image(&image, transform, &mut pixmap.as_mut());
+                crate::image::render_image(&image, ctx, transform, &mut pixmap.as_mut());
             }
         }
         usvg::filter::ImageKind::Use(ref node) => {
@@ -1030,7 +1109,7 @@ fn apply_image(
 
             if let Some(mut rtree) = crate::Tree::from_usvg_node(node) {
                 rtree.view_box.rect = rtree.view_box.rect.translate_to(0.0, 0.0);
-                rtree.render(transform, &mut pixmap.as_mut());
+                rtree.render_with_options(ctx.opt, transform, &mut pixmap.as_mut());
             }
         }
     }
@@ -1048,17 +1127,15 @@ fn apply_component_transfer(
 ) -> Result<Image, Error> {
     let mut pixmap = input.into_color_space(cs)?.take()?;
 
-    svgfilters::demultiply_alpha(pixmap.data_mut().as_rgba_mut());
-
-    svgfilters::component_transfer(
-        fe.func_b.into_svgf(),
-        fe.func_g.into_svgf(),
-        fe.func_r.into_svgf(),
-        fe.func_a.into_svgf(),
-        into_svgfilters_image_mut!(pixmap),
-    );
-
-    svgfilters::multiply_alpha(pixmap.data_mut().as_rgba_mut());
+    pixmap.demultiplied(|pixmap| {
+        svgfilters::component_transfer(
+            fe.func_b.into_svgf(),
+            fe.func_g.into_svgf(),
+            fe.func_r.into_svgf(),
+            fe.func_a.into_svgf(),
+            into_svgfilters_image_mut!(pixmap),
+        );
+    });
 
     Ok(Image::from_image(pixmap, cs))
 }
@@ -1072,8 +1149,6 @@ fn apply_color_matrix(
 
     let mut pixmap = input.into_color_space(cs)?.take()?;
 
-    svgfilters::demultiply_alpha(pixmap.data_mut().as_rgba_mut());
-
     let kind = match fe.kind {
         usvg::filter::ColorMatrixKind::Matrix(ref data) => {
             svgfilters::ColorMatrix::Matrix(data.as_slice().try_into().unwrap())
@@ -1085,9 +1160,9 @@ fn apply_color_matrix(
         }
     };
 
-    svgfilters::color_matrix(kind, into_svgfilters_image_mut!(pixmap));
-
-    svgfilters::multiply_alpha(pixmap.data_mut().as_rgba_mut());
+    pixmap.demultiplied(|pixmap| {
+        svgfilters::color_matrix(kind, into_svgfilters_image_mut!(pixmap));
+    });
 
     Ok(Image::from_image(pixmap, cs))
 }
@@ -1099,6 +1174,8 @@ fn apply_convolve_matrix(
 ) -> Result<Image, Error> {
     let mut pixmap = input.into_color_space(cs)?.take()?;
 
+    // With `preserveAlpha`, the matrix is applied to straight colors,
+    // but the result is premultiplied anyway.
     if fe.preserve_alpha {
         svgfilters::demultiply_alpha(pixmap.data_mut().as_rgba_mut());
     }
@@ -1112,11 +1189,7 @@ fn apply_convolve_matrix(
     )
     .unwrap();
 
-    let edge_mode = match fe.edge_mode {
-        usvg::filter::EdgeMode::None => svgfilters::EdgeMode::None,
-        usvg::filter::EdgeMode::Duplicate => svgfilters::EdgeMode::Duplicate,
-        usvg::filter::EdgeMode::Wrap => svgfilters::EdgeMode::Wrap,
-    };
+    let edge_mode = convert_edge_mode(fe.edge_mode);
 
     svgfilters::convolve_matrix(
         matrix,
@@ -1166,11 +1239,13 @@ fn apply_displacement_map(
     input2: Image,
 ) -> Result<Image, Error> {
     let pixmap1 = input1.into_color_space(cs)?.take()?;
-    let pixmap2 = input2.into_color_space(cs)?.take()?;
+    let mut pixmap2 = input2.into_color_space(cs)?.take()?;
+    // The displacement map is used as is and is not a part of the result.
+    svgfilters::demultiply_alpha(pixmap2.data_mut().as_rgba_mut());
 
     let mut pixmap = tiny_skia::Pixmap::try_create(region.width(), region.height())?;
 
-    let (sx, sy) = ts.get_scale();
+    let (sx, sy) = signed_scale(ts);
 
     svgfilters::displacement_map(
         fe.x_channel_selector.into_svgf(),
@@ -1187,13 +1262,14 @@ fn apply_displacement_map(
 
 fn apply_turbulence(
     fe: &usvg::filter::Turbulence,
+    ctx: &Context,
     region: IntRect,
     cs: usvg::filter::ColorInterpolation,
     ts: &usvg::Transform,
 ) -> Result<Image, Error> {
     let mut pixmap = tiny_skia::Pixmap::try_create(region.width(), region.height())?;
 
-    let (sx, sy) = ts.get_scale();
+    let (sx, sy) = signed_scale(ts);
     let (dx, dy) = ts.get_translate();
     if sx.is_fuzzy_zero() || sy.is_fuzzy_zero() {
         return Ok(Image::from_image(pixmap, cs));
@@ -1207,7 +1283,7 @@ fn apply_turbulence(
         fe.base_frequency.x.get(),
         fe.base_frequency.y.get(),
         fe.num_octaves,
-        fe.seed,
+        fe.seed.wrapping_add(ctx.opt.turbulence_seed_offset),
         fe.stitch_tiles,
         fe.kind == usvg::filter::TurbulenceKind::FractalNoise,
         into_svgfilters_image_mut!(pixmap),
@@ -1316,9 +1392,7 @@ fn apply_to_canvas(input: Image, pixmap: &mut tiny_skia::Pixmap) -> Result<(), E
 }
 
 /// Calculates Gaussian blur sigmas for the current world transform.
-///
-/// If the last flag is set, then a box blur should be used. Or IIR otherwise.
-fn resolve_std_dev(mut std_dx: f64, mut std_dy: f64) -> Option<(f64, f64, bool)> {
+fn resolve_std_dev(mut std_dx: f64, mut std_dy: f64) -> Option<(f64, f64)> {
     use usvg::ApproxEqUlps;
 
     // 'A negative value or a value of zero disables the effect of the given filter primitive
@@ -1336,11 +1410,57 @@ fn resolve_std_dev(mut std_dx: f64, mut std_dy: f64) -> Option<(f64, f64, bool)>
         std_dy = 0.0;
     }
 
+    Some((std_dx, std_dy))
+}
+
+/// Applies a Gaussian blur with separate sigmas for each axis.
+///
+/// Large sigmas are approximated using a box blur and small ones using IIR,
+/// which is chosen per axis. Otherwise, a small sigma paired with a large one,
+/// like in `stdDeviation="6 0.5"`, would be too small for a box blur and ignored.
+///
+/// `edge_mode` affects only the box blur. IIR always uses a symmetric extension,
+/// which is barely visible with a small sigma.
+fn gaussian_blur(
+    std_dx: f64,
+    std_dy: f64,
+    edge_mode: usvg::filter::EdgeMode,
+    pixmap: &mut tiny_skia::Pixmap,
+) {
     const BLUR_SIGMA_THRESHOLD: f64 = 2.0;
-    // Check that the current feGaussianBlur filter can be applied using a box blur.
-    let box_blur = std_dx >= BLUR_SIGMA_THRESHOLD || std_dy >= BLUR_SIGMA_THRESHOLD;
 
-    Some((std_dx, std_dy, box_blur))
+    let box_x = if std_dx >= BLUR_SIGMA_THRESHOLD {
+        std_dx
+    } else {
+        0.0
+    };
+    let box_y = if std_dy >= BLUR_SIGMA_THRESHOLD {
+        std_dy
+    } else {
+        0.0
+    };
+    if box_x > 0.0 || box_y > 0.0 {
+        svgfilters::box_blur(
+            box_x,
+            box_y,
+            convert_edge_mode(edge_mode),
+            into_svgfilters_image_mut!(pixmap),
+        );
+    }
+
+    let iir_x = std_dx - box_x;
+    let iir_y = std_dy - box_y;
+    if iir_x > 0.0 || iir_y > 0.0 {
+        svgfilters::iir_blur(iir_x, iir_y, into_svgfilters_image_mut!(pixmap));
+    }
+}
+
+fn convert_edge_mode(mode: usvg::filter::EdgeMode) -> svgfilters::EdgeMode {
+    match mode {
+        usvg::filter::EdgeMode::None => svgfilters::EdgeMode::None,
+        usvg::filter::EdgeMode::Duplicate => svgfilters::EdgeMode::Duplicate,
+        usvg::filter::EdgeMode::Wrap => svgfilters::EdgeMode::Wrap,
+    }
 }
 
 /// Converts coordinates from `objectBoundingBox` to the `userSpaceOnUse`.
@@ -183,6 +183,27 @@ impl IntRect {
         .unwrap()
     }
 
+    /// Creates a new `IntRect` from [`tiny_skia::Rect`].
+    ///
+    /// Uses the same rounding as [`IntRect::from_usvg`].
+    #[inline]
+    pub fn from_skia_rect(rect: tiny_skia::Rect) -> Self {
+        IntRect::new(
+            rect.x() as i32,
+            rect.y() as i32,
+            std::cmp::max(1, rect.width().round() as u32),
+            std::cmp::max(1, rect.height().round() as u32),
+        )
+        .unwrap()
+    }
+
+    /// Creates a new `IntRect` from [`tiny_skia::IntRect`].
+    #[inline]
+    pub fn from_skia(rect: tiny_skia::IntRect) -> Self {
+        // Can't fail, because `tiny_skia::IntRect` is always valid.
+        IntRect::new(rect.x(), rect.y(), rect.width(), rect.height()).unwrap()
+    }
+
     /// Returns rect's size.
     #[inline]
     pub fn size(&self) -> IntSize {
@@ -310,6 +331,27 @@ impl IntRect {
         .unwrap()
     }
 
+    /// Converts into [`tiny_skia::Rect`].
+    #[inline]
+    pub fn to_skia_rect(&self) -> tiny_skia::Rect {
+        // Can't fail, because `IntRect` is always valid.
+        tiny_skia::Rect::from_xywh(
+            self.x as f32,
+            self.y as f32,
+            self.width as f32,
+            self.height as f32,
+        )
+        .unwrap()
+    }
+
+    /// Converts into [`tiny_skia::IntRect`].
+    ///
+    /// Returns `None` when the rect's right or bottom edge doesn't fit into `i32`.
+    #[inline]
+    pub fn to_skia(&self) -> Option<tiny_skia::IntRect> {
+        tiny_skia::IntRect::from_xywh(self.x, self.y, self.width, self.height)
+    }
+
     /// Converts into `PathBbox`.
     #[inline]
     pub fn to_path_bbox(&self) -> usvg::PathBbox {
@@ -381,18 +423,30 @@ pub fn view_box_to_transform_with_clip(
     (ts, clip)
 }
 
+/// Additional [`usvg::Rect`] conversions.
 pub trait UsvgRectExt {
+    /// Converts into the smallest [`IntRect`] that contains this rect.
+    ///
+    /// The resulting rect is at least 1x1, even for subpixel rects.
     fn to_int_rect_round_out(&self) -> IntRect;
+
+    /// Converts into [`tiny_skia::Rect`].
+    ///
+    /// Returns `None` when the rect doesn't fit into `f32`.
     fn to_skia_rect(&self) -> Option<tiny_skia::Rect>;
 }
 
 impl UsvgRectExt for usvg::Rect {
     fn to_int_rect_round_out(&self) -> IntRect {
+        // Round each edge separately, otherwise a subpixel rect that crosses
+        // a pixel boundary would lose its right or bottom part.
+        let x = self.x().floor();
+        let y = self.y().floor();
         IntRect::new(
-            self.x().floor() as i32,
-            self.y().floor() as i32,
-            std::cmp::max(1, self.width().ceil() as u32),
-            std::cmp::max(1, self.height().ceil() as u32),
+            x as i32,
+            y as i32,
+            std::cmp::max(1, (self.right().ceil() - x) as u32),
+            std::cmp::max(1, (self.bottom().ceil() - y) as u32),
         )
         .unwrap()
     }
@@ -2,7 +2,8 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::render::TinySkiaPixmapMutExt;
+use crate::canvas::CanvasExt;
+use crate::render::Context;
 use crate::tree::{BBoxes, ConvTransform, Node, Tree};
 use crate::IntSize;
 
@@ -10,9 +11,13 @@ pub enum ImageKind {
     #[cfg(feature = "raster-images")]
     Raster(tiny_skia::Pixmap),
     Vector(Tree),
+    /// An image that cannot be decoded.
+    Broken,
 }
 
 pub struct Image {
+    #[cfg(feature = "profiling")]
+    pub id: String,
     pub transform: tiny_skia::Transform,
     pub view_box: usvg::ViewBox,
     pub quality: tiny_skia::FilterQuality,
@@ -39,57 +44,99 @@ pub fn convert(image: &usvg::Image, children: &mut Vec<Node>) -> Option<BBoxes>
     let kind = match image.kind {
         usvg::ImageKind::SVG(ref utree) => ImageKind::Vector(Tree::from_usvg(utree)),
         #[cfg(feature = "raster-images")]
-        _ => ImageKind::Raster(raster_images::decode_raster(image)?),
+        _ => match raster_images::decode_raster(&image.kind) {
+            Some(raster) => ImageKind::Raster(raster),
+            None => ImageKind::Broken,
+        },
         #[cfg(not(feature = "raster-images"))]
         _ => {
             log::warn!("Images decoding was disabled by a build feature.");
-            return None;
+            ImageKind::Broken
         }
     };
 
+    // Broken images are rendered only on request and must not affect bounding boxes.
+    let is_broken = matches!(kind, ImageKind::Broken);
+
     children.push(Node::Image(Image {
+        #[cfg(feature = "profiling")]
+        id: image.id.clone(),
         transform: image.transform.to_native(),
         view_box: image.view_box,
         quality,
         kind,
     }));
 
+    if is_broken {
+        return None;
+    }
+
     Some(bboxes)
 }
 
 pub fn render_image(
     image: &Image,
+    ctx: &Context,
     transform: tiny_skia::Transform,
-    pixmap: &mut tiny_skia::PixmapMut,
+    pixmap: &mut dyn crate::Canvas,
 ) {
     match image.kind {
         #[cfg(feature = "raster-images")]
         ImageKind::Raster(ref raster) => {
-            raster_images::render_raster(image, raster, transform, pixmap);
+            raster_images::render_raster(image, raster, ctx, transform, pixmap);
         }
         ImageKind::Vector(ref rtree) => {
-            render_vector(image, rtree, transform, pixmap);
+            render_vector(image, rtree, ctx, transform, pixmap);
+        }
+        ImageKind::Broken => {
+            if let Some(color) = ctx.opt.broken_image_fill {
+                render_broken(image, color, ctx, transform, pixmap);
+            }
         }
     }
 }
 
+fn render_broken(
+    image: &Image,
+    color: tiny_skia::Color,
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    pixmap: &mut dyn crate::Canvas,
+) -> Option<()> {
+    let r = image.view_box.rect;
+    let rect = tiny_skia::Rect::from_xywh(
+        r.x() as f32,
+        r.y() as f32,
+        r.width() as f32,
+        r.height() as f32,
+    )?;
+
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color(color);
+
+    let transform = ctx.raster_transform(transform.pre_concat(image.transform));
+    pixmap.fill_rect(rect, &paint, transform, None);
+
+    Some(())
+}
+
 fn render_vector(
     image: &Image,
     tree: &Tree,
+    ctx: &Context,
     transform: tiny_skia::Transform,
-    pixmap: &mut tiny_skia::PixmapMut,
+    pixmap: &mut dyn crate::Canvas,
 ) -> Option<()> {
     let img_size = IntSize::from_usvg(tree.size);
     let (ts, clip) = crate::geom::view_box_to_transform_with_clip(&image.view_box, img_size);
 
     let mut sub_pixmap = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height()).unwrap();
 
-    let source_transform = transform;
-    let transform = transform
-        .pre_concat(image.transform)
-        .pre_concat(ts.to_native());
+    let source_transform = transform.pre_concat(image.transform);
+    let transform = source_transform.pre_concat(ts.to_native());
 
-    tree.render(transform, &mut sub_pixmap.as_mut());
+    tree.render_with_context(ctx, transform, &mut sub_pixmap.as_mut())
+        .ok()?;
 
     let mask = if let Some(clip) = clip {
         let rr = tiny_skia::Rect::from_xywh(
@@ -115,15 +162,79 @@ fn render_vector(
     Some(())
 }
 
+/// Resamples an image in linearRGB using a box filter.
+///
+/// Returns `None` when the image is not scaled down.
+pub fn downscale_linear(raster: &tiny_skia::Pixmap, sx: f32, sy: f32) -> Option<tiny_skia::Pixmap> {
+    let (src_width, src_height) = (raster.width() as usize, raster.height() as usize);
+    let width = ((src_width as f32 * sx).ceil() as usize).clamp(1, src_width);
+    let height = ((src_height as f32 * sy).ceil() as usize).clamp(1, src_height);
+    if width == src_width && height == src_height {
+        return None;
+    }
+
+    let mut to_linear = [0.0f32; 256];
+    for (i, c) in to_linear.iter_mut().enumerate() {
+        *c = crate::paint_server::to_linear(i as f32 / 255.0);
+    }
+
+    let mut small = tiny_skia::Pixmap::new(width as u32, height as u32)?;
+    let src = raster.pixels();
+    let dst = small.pixels_mut();
+    // `div_ceil` requires Rust 1.73.
+    #[allow(clippy::manual_div_ceil)]
+    let div_ceil = |a: usize, b: usize| (a + b - 1) / b;
+    for y in 0..height {
+        let y0 = y * src_height / height;
+        let y1 = div_ceil((y + 1) * src_height, height);
+        for x in 0..width {
+            let x0 = x * src_width / width;
+            let x1 = div_ceil((x + 1) * src_width, width);
+
+            let mut sum = [0.0f32; 4];
+            for p in (y0..y1).flat_map(|y| &src[y * src_width + x0..y * src_width + x1]) {
+                let c = p.demultiply();
+                let a = c.alpha() as f32 / 255.0;
+                sum[0] += to_linear[c.red() as usize] * a;
+                sum[1] += to_linear[c.green() as usize] * a;
+                sum[2] += to_linear[c.blue() as usize] * a;
+                sum[3] += a;
+            }
+
+            if sum[3] == 0.0 {
+                continue;
+            }
+
+            let count = ((x1 - x0) * (y1 - y0)) as f32;
+            let alpha = (sum[3] / count * 255.0).round();
+            // Premultiply in sRGB, like all the other pixels.
+            let c = |c: f32| (crate::paint_server::from_linear(c / sum[3]) * alpha).round() as u8;
+            let alpha = alpha as u8;
+            dst[y * width + x] = tiny_skia::PremultipliedColorU8::from_rgba(
+                c(sum[0]).min(alpha),
+                c(sum[1]).min(alpha),
+                c(sum[2]).min(alpha),
+                alpha,
+            )?;
+        }
+    }
+
+    Some(small)
+}
+
+#[cfg(feature = "raster-images")]
+pub use raster_images::decode_raster;
+
 #[cfg(feature = "raster-images")]
 mod raster_images {
     use super::Image;
-    use crate::render::TinySkiaPixmapMutExt;
+    use crate::canvas::CanvasExt;
+    use crate::render::Context;
     use crate::tree::OptionLog;
     use crate::IntSize;
 
-    pub fn decode_raster(image: &usvg::Image) -> Option<tiny_skia::Pixmap> {
-        match image.kind {
+    pub fn decode_raster(kind: &usvg::ImageKind) -> Option<tiny_skia::Pixmap> {
+        match *kind {
             usvg::ImageKind::SVG(_) => None,
             usvg::ImageKind::JPEG(ref data) => {
                 decode_jpeg(data).log_none(|| log::warn!("Failed to decode a JPEG image."))
@@ -138,7 +249,17 @@ mod raster_images {
     }
 
     fn decode_png(data: &[u8]) -> Option<tiny_skia::Pixmap> {
-        tiny_skia::Pixmap::decode_png(data).ok()
+        #[allow(unused_mut)]
+        let mut pixmap = tiny_skia::Pixmap::decode_png(data).ok()?;
+
+        #[cfg(feature = "color-management")]
+        {
+            if let Some(icc) = color_management::png_icc_profile(data) {
+                color_management::convert_to_srgb(&icc, &mut pixmap);
+            }
+        }
+
+        Some(pixmap)
     }
 
     fn decode_jpeg(data: &[u8]) -> Option<tiny_skia::Pixmap> {
@@ -166,9 +287,104 @@ mod raster_images {
         let (w, h) = size.dimensions();
         let mut pixmap = tiny_skia::Pixmap::new(w, h)?;
         rgb_to_pixmap(&data, &mut pixmap);
+
+        #[cfg(feature = "color-management")]
+        {
+            if let Some(icc) = decoder.icc_profile() {
+                color_management::convert_to_srgb(&icc, &mut pixmap);
+            }
+        }
+
+        // Like browsers, we're always respecting the EXIF orientation.
+        if let Some(orientation) = decoder.exif_data().and_then(exif_orientation) {
+            pixmap = apply_orientation(pixmap, orientation)?;
+        }
+
         Some(pixmap)
     }
 
+    /// Returns the `Orientation` tag value from raw EXIF data.
+    fn exif_orientation(data: &[u8]) -> Option<u16> {
+        let big_endian = match data.get(0..2)? {
+            b"MM" => true,
+            b"II" => false,
+            _ => return None,
+        };
+
+        let read_u16 = |offset: usize| -> Option<u16> {
+            let bytes = [*data.get(offset)?, *data.get(offset + 1)?];
+            Some(if big_endian {
+                u16::from_be_bytes(bytes)
+            } else {
+                u16::from_le_bytes(bytes)
+            })
+        };
+
+        let read_u32 = |offset: usize| -> Option<u32> {
+            let hi = read_u16(offset)? as u32;
+            let lo = read_u16(offset + 2)? as u32;
+            Some(if big_endian {
+                hi << 16 | lo
+            } else {
+                lo << 16 | hi
+            })
+        };
+
+        if read_u16(2)? != 42 {
+            return None;
+        }
+
+        // Orientation is stored in the first IFD.
+        let ifd = read_u32(4)? as usize;
+        let entries = read_u16(ifd)? as usize;
+        for i in 0..entries {
+            let entry = ifd + 2 + i * 12;
+            // An `Orientation` tag with a `SHORT` type.
+            if read_u16(entry)? == 0x0112 && read_u16(entry + 2)? == 3 {
+                return read_u16(entry + 8);
+            }
+        }
+
+        None
+    }
+
+    /// Rotates and flips the pixmap according to the EXIF orientation.
+    fn apply_orientation(pixmap: tiny_skia::Pixmap, orientation: u16) -> Option<tiny_skia::Pixmap> {
+        if !(2..=8).contains(&orientation) {
+            return Some(pixmap);
+        }
+
+        let (w, h) = (pixmap.width(), pixmap.height());
+        // Orientations 5-8 swap width and height.
+        let transposed = orientation >= 5;
+        let mut new_pixmap = if transposed {
+            tiny_skia::Pixmap::new(h, w)?
+        } else {
+            tiny_skia::Pixmap::new(w, h)?
+        };
+
+        let new_width = new_pixmap.width();
+        let src = pixmap.pixels();
+        let dst = new_pixmap.pixels_mut();
+        for y in 0..h {
+            for x in 0..w {
+                let (nx, ny) = match orientation {
+                    2 => (w - 1 - x, y),
+                    3 => (w - 1 - x, h - 1 - y),
+                    4 => (x, h - 1 - y),
+                    5 => (y, x),
+                    6 => (h - 1 - y, x),
+                    7 => (h - 1 - y, w - 1 - x),
+                    _ => (y, w - 1 - x),
+                };
+
+                dst[(ny * new_width + nx) as usize] = src[(y * w + x) as usize];
+            }
+        }
+
+        Some(new_pixmap)
+    }
+
     fn decode_gif(data: &[u8]) -> Option<tiny_skia::Pixmap> {
         let mut decoder = gif::DecodeOptions::new();
         decoder.set_color_output(gif::ColorOutput::RGBA);
@@ -217,8 +433,9 @@ mod raster_images {
     pub(crate) fn render_raster(
         image: &Image,
         raster: &tiny_skia::Pixmap,
+        ctx: &Context,
         transform: tiny_skia::Transform,
-        pixmap: &mut tiny_skia::PixmapMut,
+        pixmap: &mut dyn crate::Canvas,
     ) -> Option<()> {
         let img_size = IntSize::new(raster.width(), raster.height())?;
         let r = image_rect(&image.view_box, img_size);
@@ -238,15 +455,23 @@ mod raster_images {
             r.y() as f32,
         );
 
-        let pattern = tiny_skia::Pattern::new(
-            raster.as_ref(),
-            tiny_skia::SpreadMode::Pad,
-            image.quality,
-            1.0,
-            ts,
-        );
-        let mut paint = tiny_skia::Paint::default();
-        paint.shader = pattern;
+        let transform = transform.pre_concat(image.transform);
+
+        // Image pixel size on the canvas.
+        let (sx, sy) = {
+            let ts = transform.pre_concat(ts);
+            (
+                (ts.sx * ts.sx + ts.ky * ts.ky).sqrt(),
+                (ts.kx * ts.kx + ts.sy * ts.sy).sqrt(),
+            )
+        };
+
+        let mut quality = image.quality;
+        if let Some(limit) = ctx.opt.smooth_upscale_limit {
+            if sx.min(sy) > limit {
+                quality = tiny_skia::FilterQuality::Nearest;
+            }
+        }
 
         let mask = if image.view_box.aspect.slice {
             let r = image.view_box.rect;
@@ -262,12 +487,76 @@ mod raster_images {
             None
         };
 
-        let transform = transform.pre_concat(image.transform);
+        // An image that is only moved by whole pixels maps onto the canvas pixel by pixel,
+        // therefore resampling would only soften it.
+        let canvas_ts = ctx.raster_transform(transform.pre_concat(ts));
+        if let Some((x, y)) = integer_translation(canvas_ts, img_size) {
+            let paint = tiny_skia::PixmapPaint {
+                quality: tiny_skia::FilterQuality::Nearest,
+                ..tiny_skia::PixmapPaint::default()
+            };
+            pixmap.draw_pixmap(
+                x,
+                y,
+                raster.as_ref(),
+                &paint,
+                tiny_skia::Transform::identity(),
+                mask.as_ref(),
+            );
+            return Some(());
+        }
+
+        let mut downscaled = None;
+        if ctx.opt.gamma_correct_downscaling && quality != tiny_skia::FilterQuality::Nearest {
+            downscaled = super::downscale_linear(raster, sx, sy);
+        }
+
+        let (raster, ts) = match downscaled {
+            Some(ref small) => (
+                small,
+                ts.pre_scale(
+                    raster.width() as f32 / small.width() as f32,
+                    raster.height() as f32 / small.height() as f32,
+                ),
+            ),
+            None => (raster, ts),
+        };
+
+        let pattern = tiny_skia::Pattern::new(
+            raster.as_ref(),
+            tiny_skia::SpreadMode::Pad,
+            quality,
+            1.0,
+            ts,
+        );
+        let mut paint = tiny_skia::Paint::default();
+        paint.shader = pattern;
+
+        let transform = ctx.raster_transform(transform);
         pixmap.fill_rect(rect, &paint, transform, mask.as_ref());
 
         Some(())
     }
 
+    /// Returns an offset when a transform maps an image of the specified size
+    /// onto the pixel grid with a translation by whole pixels.
+    fn integer_translation(ts: tiny_skia::Transform, size: IntSize) -> Option<(i32, i32)> {
+        // Allows for rounding errors accumulated by nested transforms,
+        // as long as image corners are off by less than 1/1000 of a pixel.
+        let is_zero = |n: f32| n.abs() < 0.001;
+        let (w, h) = (size.width() as f32, size.height() as f32);
+        let is_translate = is_zero((ts.sx - 1.0) * w)
+            && is_zero((ts.sy - 1.0) * h)
+            && is_zero(ts.kx * h)
+            && is_zero(ts.ky * w);
+
+        if is_translate && is_zero(ts.tx - ts.tx.round()) && is_zero(ts.ty - ts.ty.round()) {
+            Some((ts.tx.round() as i32, ts.ty.round() as i32))
+        } else {
+            None
+        }
+    }
+
     /// Calculates an image rect depending on the provided view box.
     fn image_rect(view_box: &usvg::ViewBox, img_size: IntSize) -> usvg::Rect {
         let new_size = img_size.to_size().fit_view_box(view_box);
@@ -281,4 +570,52 @@ mod raster_images {
 
         new_size.to_rect(x, y)
     }
+
+    #[cfg(feature = "color-management")]
+    mod color_management {
+        use crate::tree::OptionLog;
+
+        pub fn png_icc_profile(data: &[u8]) -> Option<Vec<u8>> {
+            let reader = png::Decoder::new(data).read_info().ok()?;
+            let icc = reader.info().icc_profile.as_ref()?;
+            Some(icc.to_vec())
+        }
+
+        /// Converts pixmap's colors from the color space described by an ICC profile into sRGB.
+        pub fn convert_to_srgb(icc: &[u8], pixmap: &mut tiny_skia::Pixmap) {
+            convert_to_srgb_impl(icc, pixmap)
+                .log_none(|| log::warn!("Failed to apply an embedded ICC profile."));
+        }
+
+        fn convert_to_srgb_impl(icc: &[u8], pixmap: &mut tiny_skia::Pixmap) -> Option<()> {
+            let input = qcms::Profile::new_from_slice(icc, false)?;
+            if input.is_sRGB() {
+                return Some(());
+            }
+
+            let mut output = qcms::Profile::new_sRGB();
+            output.precache_output_transform();
+            let transform = qcms::Transform::new(
+                &input,
+                &output,
+                qcms::DataType::RGBA8,
+                qcms::Intent::default(),
+            )?;
+
+            // ICC transforms work on unpremultiplied colors.
+            let mut data = Vec::with_capacity(pixmap.data().len());
+            for p in pixmap.pixels() {
+                let c = p.demultiply();
+                data.extend_from_slice(&[c.red(), c.green(), c.blue(), c.alpha()]);
+            }
+
+            transform.apply(&mut data);
+
+            for (p, c) in pixmap.pixels_mut().iter_mut().zip(data.chunks_exact(4)) {
+                *p = tiny_skia::ColorU8::from_rgba(c[0], c[1], c[2], c[3]).premultiply();
+            }
+
+            Some(())
+        }
+    }
 }
@@ -18,15 +18,34 @@
 pub use tiny_skia;
 pub use usvg;
 
+mod atlas;
+mod canvas;
 mod clip;
+mod compat;
+mod debug;
+mod dither;
+#[cfg(any(feature = "encode-webp", feature = "encode-avif"))]
+mod encode;
 mod filter;
 mod geom;
 mod image;
 mod mask;
+mod options;
 mod paint_server;
 mod path;
+mod pool;
 mod render;
+mod renderer;
+pub mod testing;
 mod tree;
 
-pub use crate::geom::{IntRect, IntSize};
+pub use crate::canvas::Canvas;
+pub use crate::compat::Unsupported;
+pub use crate::geom::{IntRect, IntSize, UsvgRectExt};
+pub use crate::options::{
+    ClassFilter, DebugBbox, GroupRenderedCallback, OpacityOverride, RenderOptions,
+};
+pub use crate::pool::PixmapPool;
+pub use crate::render::{render_usvg_path, RenderError, TextureFormat};
+pub use crate::renderer::Renderer;
 pub use crate::tree::Tree;
@@ -72,6 +72,11 @@ pub fn apply(
 
     let mut mask_pixmap = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height()).unwrap();
 
+    let content_ctx = Context {
+        anti_alias: ctx.anti_alias && ctx.opt.mask_anti_alias,
+        ..*ctx
+    };
+
     {
         // TODO: only when needed
         // Mask has to be clipped by mask.region
@@ -79,14 +84,14 @@ pub fn apply(
         alpha_mask.fill_path(
             &tiny_skia::PathBuilder::from_rect(mask.region),
             tiny_skia::FillRule::Winding,
-            true,
+            content_ctx.anti_alias,
             transform,
         );
 
         let content_transform = transform.pre_concat(mask.content_transform);
         crate::render::render_nodes(
             &mask.children,
-            ctx,
+            &content_ctx,
             content_transform,
             &mut mask_pixmap.as_mut(),
         );
@@ -0,0 +1,380 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::IntRect;
+
+/// Rendering options.
+///
+/// Unlike [`usvg::Options`], those options do not affect parsing
+/// and can be changed between renders of the same [`Tree`](crate::Tree).
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    /// A value that will be added to the `seed` of each `feTurbulence` filter primitive.
+    ///
+    /// Allows changing the generated noise without editing the SVG.
+    /// The same effective seed always produces the same output.
+    ///
+    /// Default: 0
+    pub turbulence_seed_offset: i32,
+
+    /// Aligns text baselines to the pixel grid.
+    ///
+    /// Text is rendered as paths, which are positioned with a subpixel precision.
+    /// Snapping the baseline produces sharper results for small text.
+    ///
+    /// Affects only horizontal, non-rotated text that was converted into paths
+    /// using [`usvg::TreeTextToPath::convert_text`].
+    ///
+    /// Default: false
+    pub snap_text_baseline: bool,
+
+    /// Renders path fills.
+    ///
+    /// Can be disabled to produce a stroke-only output.
+    /// Affects masks and patterns content as well, but not clipping paths.
+    ///
+    /// Default: true
+    pub render_fills: bool,
+
+    /// Renders path strokes.
+    ///
+    /// Can be disabled to produce a fill-only output.
+    /// Affects masks and patterns content as well, but not clipping paths.
+    ///
+    /// Default: true
+    pub render_strokes: bool,
+
+    /// Rounds transform coefficients to a fixed precision before rasterization.
+    ///
+    /// Different platforms can produce slightly different transforms for the same input
+    /// due to floating-point rounding. Which then leads to 1-bit differences
+    /// in anti-aliased pixels. Rounding hides those differences in most cases.
+    ///
+    /// Operations that remain nondeterministic across platforms:
+    ///
+    /// - tiny-skia's SIMD pipelines, which can use fused multiply-add on some targets.
+    /// - Filters that rely on `f64` transcendental functions from the platform's libm,
+    ///   like `feColorMatrix type="hueRotate"`, `feComponentTransfer type="gamma"`
+    ///   and lighting filters.
+    ///
+    /// Default: false
+    pub deterministic: bool,
+
+    /// The maximum number of bytes a pixmap allocated by
+    /// [`Tree::try_render_to_pixmap`](crate::Tree::try_render_to_pixmap) can occupy.
+    ///
+    /// `None` means no limit.
+    ///
+    /// Default: None
+    pub memory_budget: Option<usize>,
+
+    /// Anti-aliases the content of masks.
+    ///
+    /// Can be disabled to produce masks with hard edges.
+    /// The transparency of the mask content itself is preserved.
+    ///
+    /// Default: true
+    pub mask_anti_alias: bool,
+
+    /// Draws bounding boxes on top of the rendered image.
+    ///
+    /// Useful for debugging.
+    ///
+    /// Default: DebugBbox::None
+    pub draw_bboxes: DebugBbox,
+
+    /// A color used to fill the area of raster images that cannot be decoded.
+    ///
+    /// Helps to detect broken images. `None` means that such images are not rendered.
+    ///
+    /// Images that cannot be loaded at all are removed during parsing and are not affected.
+    ///
+    /// Broken images do not affect bounding boxes, [`Tree::content_area`](crate::Tree::content_area)
+    /// and [`Tree::is_empty`](crate::Tree::is_empty). Therefore their placeholders
+    /// can be clipped by the layers of parent groups.
+    ///
+    /// Default: None
+    pub broken_image_fill: Option<tiny_skia::Color>,
+
+    /// A background color the rendered image will be composited onto.
+    ///
+    /// The resulting image is fully opaque, which is useful for formats without
+    /// an alpha channel, like JPEG. A semi-transparent color is flattened onto black.
+    ///
+    /// Debug bounding boxes are drawn before flattening.
+    ///
+    /// Default: None
+    pub flatten_onto: Option<tiny_skia::Color>,
+
+    /// A pool group layers will be taken from.
+    ///
+    /// Can be shared between renders to reduce allocations.
+    /// `None` means that layers are allocated on each render.
+    ///
+    /// Default: None
+    pub pixmap_pool: Option<std::sync::Arc<crate::PixmapPool>>,
+
+    /// Skips groups that are completely outside the pixmap.
+    ///
+    /// A group's bounding box includes strokes and filter regions,
+    /// therefore culling doesn't affect the output. Can be disabled for debugging.
+    ///
+    /// Default: true
+    pub cull_offscreen: bool,
+
+    /// Applies ordered dithering to the rendered image.
+    ///
+    /// Reduces banding of large, subtle gradients on 8-bit output.
+    /// Affects only smooth color transitions. Flat areas and sharp edges are preserved.
+    ///
+    /// Applied after [`flatten_onto`](Self::flatten_onto).
+    ///
+    /// Default: false
+    pub dither: bool,
+
+    /// The maximum nesting depth of groups.
+    ///
+    /// Deeper groups are skipped with a warning to prevent a stack overflow
+    /// on malicious or generated trees.
+    /// Groups of nested SVG images are counted as well.
+    ///
+    /// Default: 1024
+    pub max_recursion_depth: usize,
+
+    /// A callback invoked for each rendered group layer.
+    ///
+    /// Called after filters, clip paths and masks were applied,
+    /// right before the layer is composited into its parent.
+    /// Groups that do not require a layer are not reported.
+    ///
+    /// Default: None
+    pub on_group_rendered: Option<GroupRenderedCallback>,
+
+    /// Downscales raster images in the linearRGB color space.
+    ///
+    /// Averaging pixels in sRGB darkens downscaled images with fine details,
+    /// like photos of textures. When enabled, scaled down raster images are resampled
+    /// in linearRGB first, which is slower but preserves perceived brightness.
+    /// Doesn't affect images with `image-rendering` set to `optimizeSpeed`.
+    ///
+    /// Default: false
+    pub gamma_correct_downscaling: bool,
+
+    /// Renders each translucent stroke into a separate layer.
+    ///
+    /// A stroke is a single shape, but thin strokes are rasterized segment by segment,
+    /// which makes self-overlapping parts of a translucent stroke darker.
+    /// When enabled, such strokes are composited at once, which is slower.
+    ///
+    /// Default: false
+    pub isolate_translucent_strokes: bool,
+
+    /// The maximum scale factor at which raster images are smoothed.
+    ///
+    /// Images scaled up by a bigger factor are rendered using nearest-neighbor sampling,
+    /// which is faster and keeps tiny images, like pixel art, crisp.
+    /// `None` means that images are always smoothed, unless `image-rendering`
+    /// is set to `optimizeSpeed`.
+    ///
+    /// Default: None
+    pub smooth_upscale_limit: Option<f32>,
+
+    /// A filter for group classes.
+    ///
+    /// Groups whose classes are all rejected by the filter are skipped with their children.
+    /// Groups without classes are always rendered.
+    /// Only `g` and `use` elements retain their classes.
+    ///
+    /// Allows rendering a single layer, like a print one, of an SVG with multiple layers.
+    ///
+    /// Default: None
+    pub class_filter: Option<ClassFilter>,
+
+    /// Clamps filter regions to 4x the canvas size.
+    ///
+    /// Filter regions are computed from the filter's `x`, `y`, `width` and `height`
+    /// and can be arbitrary large. Clamping prevents huge regions from tanking
+    /// the performance, but can cut off content that a filter moves onto the canvas,
+    /// like a drop shadow of an element located far outside of it.
+    /// When disabled, filter regions are used as declared.
+    ///
+    /// Default: true
+    pub clamp_filter_regions: bool,
+
+    /// Deallocates filter primitive results as soon as they are no longer referenced.
+    ///
+    /// By default, results of all primitives of a filter are kept until the filter is applied,
+    /// since any of them can be referenced later. In the low memory mode, a result is deallocated
+    /// once the remaining primitives do not reference it. Which is slightly slower.
+    ///
+    /// Buffers are not reused between primitives. Each primitive still allocates its own result
+    /// and can copy its inputs, while they are alive. Therefore, this mode reduces
+    /// the number of results kept by long filters, but not the memory required
+    /// by a single primitive, which is the peak for short chains.
+    ///
+    /// Default: false
+    pub low_memory: bool,
+
+    /// A color used to fill the area of text glyphs missing in all fonts.
+    ///
+    /// Missing glyphs are rendered using the `.notdef` glyph, which is often empty,
+    /// making missing fonts hard to spot. When set, a box sized to the advance
+    /// of missing glyphs is drawn underneath them. Affects only filled text
+    /// that was converted into paths using [`usvg::TreeTextToPath::convert_text`].
+    ///
+    /// Default: None
+    pub missing_text_placeholder: Option<tiny_skia::Color>,
+
+    /// A callback that overrides the opacity of nodes by ID.
+    ///
+    /// Allows animating the opacity of individual elements without re-parsing the SVG.
+    /// Replaces the opacity of groups and is multiplied into the paint opacity of paths,
+    /// therefore an overlapping fill and stroke of the same path remain distinguishable.
+    /// Values are clamped to the 0..1 range. Nodes without an ID are not affected.
+    ///
+    /// Default: None
+    pub opacity_override: Option<OpacityOverride>,
+
+    /// Snaps axis-aligned path segments to the device pixel grid.
+    ///
+    /// Produces crisp edges for rectangles and lines positioned at fractional coordinates,
+    /// which is useful for UI rendering. Fills are snapped to pixel boundaries,
+    /// while odd-width strokes are snapped to pixel centers.
+    /// Shapes can shift by up to half a pixel. Diagonal lines and curves are not snapped.
+    ///
+    /// Default: false
+    pub pixel_snap: bool,
+
+    /// Encodes the rendered image in linear RGB instead of sRGB.
+    ///
+    /// Rendering and compositing are still done in sRGB, and only the final image
+    /// is converted, which changes pixel values. Useful when the image is passed
+    /// to a pipeline that expects linear-light input, to avoid encoding it twice.
+    /// Dark colors lose precision, since the image still has 8 bits per channel.
+    ///
+    /// Applied after [`RenderOptions::flatten_onto`] and before [`RenderOptions::dither`].
+    ///
+    /// Default: false
+    pub linear_output: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            turbulence_seed_offset: 0,
+            snap_text_baseline: false,
+            render_fills: true,
+            render_strokes: true,
+            deterministic: false,
+            memory_budget: None,
+            mask_anti_alias: true,
+            draw_bboxes: DebugBbox::None,
+            broken_image_fill: None,
+            flatten_onto: None,
+            pixmap_pool: None,
+            cull_offscreen: true,
+            dither: false,
+            max_recursion_depth: 1024,
+            on_group_rendered: None,
+            gamma_correct_downscaling: false,
+            isolate_translucent_strokes: false,
+            smooth_upscale_limit: None,
+            class_filter: None,
+            clamp_filter_regions: true,
+            low_memory: false,
+            missing_text_placeholder: None,
+            opacity_override: None,
+            pixel_snap: false,
+            linear_output: false,
+        }
+    }
+}
+
+/// Bounding boxes that should be drawn on top of the rendered image.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DebugBbox {
+    /// Draw nothing.
+    None,
+    /// Draw bounding boxes of paths and images in red.
+    Nodes,
+    /// Draw bounding boxes of paths and images in red,
+    /// filter regions in blue and clip paths bounding boxes in green.
+    All,
+}
+
+/// A group layer inspection callback.
+///
+/// Receives a group ID, which can be empty, the group layer
+/// and the layer region on the parent canvas.
+#[derive(Clone)]
+pub struct GroupRenderedCallback(std::sync::Arc<GroupRenderedFn>);
+
+type GroupRenderedFn = dyn Fn(&str, &tiny_skia::Pixmap, IntRect) + Send + Sync;
+
+impl GroupRenderedCallback {
+    /// Creates a new callback.
+    pub fn new(f: impl Fn(&str, &tiny_skia::Pixmap, IntRect) + Send + Sync + 'static) -> Self {
+        GroupRenderedCallback(std::sync::Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, id: &str, pixmap: &tiny_skia::Pixmap, rect: IntRect) {
+        (self.0)(id, pixmap, rect)
+    }
+}
+
+impl std::fmt::Debug for GroupRenderedCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("GroupRenderedCallback(..)")
+    }
+}
+
+/// A group class filter.
+///
+/// Receives a single class and returns `true` when it's allowed.
+#[derive(Clone)]
+pub struct ClassFilter(std::sync::Arc<ClassFilterFn>);
+
+type ClassFilterFn = dyn Fn(&str) -> bool + Send + Sync;
+
+impl ClassFilter {
+    /// Creates a new filter.
+    pub fn new(f: impl Fn(&str) -> bool + Send + Sync + 'static) -> Self {
+        ClassFilter(std::sync::Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, class: &str) -> bool {
+        (self.0)(class)
+    }
+}
+
+impl std::fmt::Debug for ClassFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("ClassFilter(..)")
+    }
+}
+
+/// A node opacity override callback.
+///
+/// Receives a node ID and returns a new opacity or `None` to keep the original one.
+#[derive(Clone)]
+pub struct OpacityOverride(std::sync::Arc<OpacityOverrideFn>);
+
+type OpacityOverrideFn = dyn Fn(&str) -> Option<f32> + Send + Sync;
+
+impl OpacityOverride {
+    /// Creates a new callback.
+    pub fn new(f: impl Fn(&str) -> Option<f32> + Send + Sync + 'static) -> Self {
+        OpacityOverride(std::sync::Arc::new(f))
+    }
+
+    pub(crate) fn call(&self, id: &str) -> Option<f32> {
+        (self.0)(id)
+    }
+}
+
+impl std::fmt::Debug for OpacityOverride {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("OpacityOverride(..)")
+    }
+}
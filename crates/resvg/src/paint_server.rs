@@ -102,7 +102,7 @@ fn convert_base_gradient(
         gradient.transform.to_native()
     };
 
-    let mut points = Vec::with_capacity(gradient.stops.len());
+    let mut stops = Vec::with_capacity(gradient.stops.len());
     for stop in &gradient.stops {
         let alpha = stop.opacity * opacity;
         let color = tiny_skia::Color::from_rgba8(
@@ -111,15 +111,80 @@ fn convert_base_gradient(
             stop.color.blue,
             alpha.to_u8(),
         );
-        points.push(tiny_skia::GradientStop::new(
-            stop.offset.get() as f32,
-            color,
-        ))
+        stops.push((stop.offset.get() as f32, color));
     }
 
+    let points = match gradient.color_interpolation {
+        usvg::filter::ColorInterpolation::SRGB => stops
+            .iter()
+            .map(|(offset, color)| tiny_skia::GradientStop::new(*offset, *color))
+            .collect(),
+        usvg::filter::ColorInterpolation::LinearRGB => linear_rgb_stops(&stops),
+    };
+
     Some((mode, transform, points))
 }
 
+/// Converts an sRGB color channel in a 0..1 range into linearRGB.
+pub(crate) fn to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Converts a linearRGB color channel in a 0..1 range into sRGB.
+pub(crate) fn from_linear(c: f32) -> f32 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Approximates stop colors interpolation in the linearRGB color space.
+///
+/// tiny-skia can interpolate only in sRGB, therefore each pair of stops is split
+/// into multiple segments with colors interpolated in linearRGB and converted back to sRGB.
+fn linear_rgb_stops(stops: &[(f32, tiny_skia::Color)]) -> Vec<tiny_skia::GradientStop> {
+    const SEGMENTS: usize = 16;
+
+    let mut points = Vec::with_capacity(stops.len() * SEGMENTS);
+    for pair in stops.windows(2) {
+        let (offset1, c1) = pair[0];
+        let (offset2, c2) = pair[1];
+
+        points.push(tiny_skia::GradientStop::new(offset1, c1));
+
+        // Stops at the same offset produce a hard edge.
+        if offset2 - offset1 <= f32::EPSILON {
+            continue;
+        }
+
+        for i in 1..SEGMENTS {
+            let t = i as f32 / SEGMENTS as f32;
+            let mix = |a: f32, b: f32| a + (b - a) * t;
+            let mix_linear = |a: f32, b: f32| from_linear(mix(to_linear(a), to_linear(b)));
+            let color = tiny_skia::Color::from_rgba(
+                mix_linear(c1.red(), c2.red()),
+                mix_linear(c1.green(), c2.green()),
+                mix_linear(c1.blue(), c2.blue()),
+                mix(c1.alpha(), c2.alpha()),
+            );
+            if let Some(color) = color {
+                points.push(tiny_skia::GradientStop::new(mix(offset1, offset2), color));
+            }
+        }
+    }
+
+    if let Some((offset, color)) = stops.last() {
+        points.push(tiny_skia::GradientStop::new(*offset, *color));
+    }
+
+    points
+}
+
 fn convert_pattern(
     pattern: &usvg::Pattern,
     opacity: usvg::Opacity,
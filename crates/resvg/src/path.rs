@@ -4,39 +4,57 @@
 
 use std::rc::Rc;
 
+use crate::geom::UsvgRectExt;
 use crate::paint_server::Paint;
 use crate::render::Context;
 use crate::tree::{BBoxes, ConvTransform, Node, TinySkiaRectExt};
 
 pub struct FillPath {
+    pub id: String,
     pub transform: tiny_skia::Transform,
     pub paint: Paint,
     pub rule: tiny_skia::FillRule,
     pub anti_alias: bool,
+    /// Text baseline position. Set only for paths converted from text.
+    pub text_baseline: Option<f32>,
     pub path: Rc<tiny_skia::Path>,
 }
 
 pub struct StrokePath {
+    pub id: String,
     pub transform: tiny_skia::Transform,
     pub paint: Paint,
     pub stroke: tiny_skia::Stroke,
     pub anti_alias: bool,
+    /// Text baseline position. Set only for paths converted from text.
+    pub text_baseline: Option<f32>,
     pub path: Rc<tiny_skia::Path>,
 }
 
+/// An area occupied by glyphs missing in all fonts.
+///
+/// Rendered only when [`RenderOptions::missing_text_placeholder`](crate::RenderOptions::missing_text_placeholder) is set.
+pub struct TextPlaceholder {
+    pub transform: tiny_skia::Transform,
+    pub rect: tiny_skia::Rect,
+}
+
 pub fn convert(upath: &usvg::Path, children: &mut Vec<Node>) -> Option<BBoxes> {
+    let placeholder_bboxes = convert_text_placeholder(upath, children);
+
     let transform = upath.transform.to_native();
     let anti_alias = upath.rendering_mode.use_shape_antialiasing();
     let path = match convert_path_data(&upath.data) {
         Some(v) => Rc::new(v),
-        None => return None,
+        // Paths consisting of missing glyphs only are empty.
+        None => return placeholder_bboxes,
     };
 
-    let fill_path = upath.fill.as_ref().and_then(|ufill| {
+    let mut fill_path = upath.fill.as_ref().and_then(|ufill| {
         convert_fill_path(ufill, path.clone(), transform, upath.text_bbox, anti_alias)
     });
 
-    let stroke_path = upath.stroke.as_ref().and_then(|ustroke| {
+    let mut stroke_path = upath.stroke.as_ref().and_then(|ustroke| {
         convert_stroke_path(
             ustroke,
             path.clone(),
@@ -46,11 +64,21 @@ pub fn convert(upath: &usvg::Path, children: &mut Vec<Node>) -> Option<BBoxes> {
         )
     });
 
+    let text_baseline = upath.text_baseline.map(|y| y as f32);
+    if let Some((ref mut path, _, _)) = fill_path {
+        path.id = upath.id.clone();
+        path.text_baseline = text_baseline;
+    }
+    if let Some((ref mut path, _, _)) = stroke_path {
+        path.id = upath.id.clone();
+        path.text_baseline = text_baseline;
+    }
+
     if fill_path.is_none() && stroke_path.is_none() {
-        return None;
+        return placeholder_bboxes;
     }
 
-    let mut bboxes = BBoxes::default();
+    let mut bboxes = placeholder_bboxes.unwrap_or_default();
 
     if let Some((_, l_bbox, o_bbox)) = fill_path {
         bboxes.layer = bboxes.layer.expand(l_bbox);
@@ -90,6 +118,26 @@ pub fn convert(upath: &usvg::Path, children: &mut Vec<Node>) -> Option<BBoxes> {
     Some(bboxes)
 }
 
+fn convert_text_placeholder(upath: &usvg::Path, children: &mut Vec<Node>) -> Option<BBoxes> {
+    let bbox = upath.missing_glyphs_bbox?;
+    if upath.visibility != usvg::Visibility::Visible || upath.fill.is_none() {
+        return None;
+    }
+
+    let rect = bbox.to_skia_rect()?;
+    children.push(Node::TextPlaceholder(TextPlaceholder {
+        transform: upath.transform.to_native(),
+        rect,
+    }));
+
+    let object = bbox.to_path_bbox();
+    Some(BBoxes {
+        object,
+        transformed_object: object.transform(&upath.transform)?,
+        layer: object,
+    })
+}
+
 fn convert_fill_path(
     ufill: &usvg::Fill,
     path: Rc<tiny_skia::Path>,
@@ -119,6 +167,8 @@ fn convert_fill_path(
         paint,
         rule,
         anti_alias,
+        text_baseline: None,
+        id: String::new(),
         path,
     };
 
@@ -182,6 +232,8 @@ fn convert_stroke_path(
         paint,
         stroke: stroke,
         anti_alias,
+        text_baseline: None,
+        id: String::new(),
         path,
     };
 
@@ -211,12 +263,31 @@ fn convert_path_data(path: &usvg::PathData) -> Option<tiny_skia::Path> {
     pb.finish()
 }
 
+pub fn render_text_placeholder(
+    placeholder: &TextPlaceholder,
+    ctx: &Context,
+    transform: tiny_skia::Transform,
+    pixmap: &mut dyn crate::Canvas,
+) {
+    let color = match ctx.opt.missing_text_placeholder {
+        Some(v) => v,
+        None => return,
+    };
+
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color(color);
+    paint.anti_alias = ctx.anti_alias;
+
+    let transform = ctx.raster_transform(transform.pre_concat(placeholder.transform));
+    pixmap.fill_rect(placeholder.rect, &paint, transform, None);
+}
+
 pub fn render_fill_path(
     path: &FillPath,
     blend_mode: tiny_skia::BlendMode,
     ctx: &Context,
     transform: tiny_skia::Transform,
-    pixmap: &mut tiny_skia::PixmapMut,
+    pixmap: &mut dyn crate::Canvas,
 ) -> Option<()> {
     let pattern_pixmap;
     let mut paint = tiny_skia::Paint::default();
@@ -239,11 +310,29 @@ pub fn render_fill_path(
         }
     }
 
-    paint.anti_alias = path.anti_alias;
+    if let Some(opacity) = ctx.opacity_override(&path.id) {
+        paint.shader.apply_opacity(opacity);
+    }
+
+    paint.anti_alias = path.anti_alias && ctx.anti_alias;
     paint.blend_mode = blend_mode;
 
-    let transform = transform.pre_concat(path.transform);
-    pixmap.fill_path(&path.path, &paint, path.rule, transform, None);
+    let mut transform = transform.pre_concat(path.transform);
+    if ctx.opt.snap_text_baseline {
+        transform = snap_baseline(path.text_baseline, transform);
+    }
+
+    let transform = ctx.raster_transform(transform);
+    let snapped;
+    let mut upath = &*path.path;
+    if ctx.opt.pixel_snap {
+        if let Some(v) = snap_to_pixels(upath, transform, 0.0) {
+            snapped = v;
+            upath = &snapped;
+        }
+    }
+
+    pixmap.fill_path(upath, &paint, path.rule, transform, None);
 
     Some(())
 }
@@ -253,7 +342,7 @@ pub fn render_stroke_path(
     blend_mode: tiny_skia::BlendMode,
     ctx: &Context,
     transform: tiny_skia::Transform,
-    pixmap: &mut tiny_skia::PixmapMut,
+    pixmap: &mut dyn crate::Canvas,
 ) -> Option<()> {
     let pattern_pixmap;
     let mut paint = tiny_skia::Paint::default();
@@ -276,13 +365,211 @@ pub fn render_stroke_path(
         }
     }
 
-    paint.anti_alias = path.anti_alias;
+    if let Some(opacity) = ctx.opacity_override(&path.id) {
+        paint.shader.apply_opacity(opacity);
+    }
+
+    paint.anti_alias = path.anti_alias && ctx.anti_alias;
     paint.blend_mode = blend_mode;
 
     // TODO: fallback to a stroked path when possible
 
-    let transform = transform.pre_concat(path.transform);
-    pixmap.stroke_path(&path.path, &paint, &path.stroke, transform, None);
+    let mut transform = transform.pre_concat(path.transform);
+    if ctx.opt.snap_text_baseline {
+        transform = snap_baseline(path.text_baseline, transform);
+    }
+
+    let transform = ctx.raster_transform(transform);
+    let snapped;
+    let mut upath = &*path.path;
+    if ctx.opt.pixel_snap {
+        // Odd-width strokes are crisp only when centered on pixels.
+        let scale = (transform.sx * transform.sy - transform.kx * transform.ky)
+            .abs()
+            .sqrt();
+        let width = path.stroke.width * scale;
+        let offset = if width.round() as i64 % 2 == 0 && width >= 0.5 {
+            0.0
+        } else {
+            0.5
+        };
+
+        if let Some(v) = snap_to_pixels(upath, transform, offset) {
+            snapped = v;
+            upath = &snapped;
+        }
+    }
+
+    if ctx.opt.isolate_translucent_strokes
+        && blend_mode == tiny_skia::BlendMode::SourceOver
+        && !paint.shader.is_opaque()
+    {
+        return render_isolated_stroke(upath, &path.stroke, paint, transform, pixmap);
+    }
+
+    pixmap.stroke_path(upath, &paint, &path.stroke, transform, None);
 
     Some(())
 }
+
+/// Renders a stroke into a separate layer, so overlapping segments would not accumulate.
+fn render_isolated_stroke(
+    path: &tiny_skia::Path,
+    stroke: &tiny_skia::Stroke,
+    mut paint: tiny_skia::Paint,
+    transform: tiny_skia::Transform,
+    pixmap: &mut dyn crate::Canvas,
+) -> Option<()> {
+    let bbox = path.stroke(stroke, 1.0)?.transform(transform)?.bounds();
+
+    // Expand by 1px for anti-aliased and hairline pixels.
+    let x = (bbox.left().floor() as i32 - 1).max(0);
+    let y = (bbox.top().floor() as i32 - 1).max(0);
+    let right = (bbox.right().ceil() as i32 + 1).min(pixmap.width() as i32);
+    let bottom = (bbox.bottom().ceil() as i32 + 1).min(pixmap.height() as i32);
+    if right <= x || bottom <= y {
+        return None;
+    }
+
+    let mut layer = tiny_skia::Pixmap::new((right - x) as u32, (bottom - y) as u32)?;
+
+    // Overlapping parts replace each other instead of being blended.
+    paint.blend_mode = tiny_skia::BlendMode::Source;
+    let transform = transform.post_translate(-x as f32, -y as f32);
+    layer.stroke_path(path, &paint, stroke, transform, None);
+
+    pixmap.draw_pixmap(
+        x,
+        y,
+        layer.as_ref(),
+        &tiny_skia::PixmapPaint::default(),
+        tiny_skia::Transform::identity(),
+        None,
+    );
+
+    Some(())
+}
+
+/// Moves axis-aligned segments of a path onto the device pixel grid.
+///
+/// Coordinates are snapped to `offset` inside a pixel, which is 0 for pixel boundaries
+/// and 0.5 for pixel centers. Lines that are not axis-aligned and curves are left as is,
+/// but can be moved slightly when they share a point with a snapped segment.
+///
+/// Returns `None` when `ts` is not invertible.
+fn snap_to_pixels(
+    path: &tiny_skia::Path,
+    ts: tiny_skia::Transform,
+    offset: f32,
+) -> Option<tiny_skia::Path> {
+    use tiny_skia::PathSegment;
+
+    let inv_ts = ts.invert()?;
+
+    let segments: Vec<_> = path.segments().collect();
+    let mut points = Vec::with_capacity(path.len());
+    for segment in &segments {
+        match *segment {
+            PathSegment::MoveTo(p1) | PathSegment::LineTo(p1) => points.push(p1),
+            PathSegment::QuadTo(p1, p2) => points.extend_from_slice(&[p1, p2]),
+            PathSegment::CubicTo(p1, p2, p3) => points.extend_from_slice(&[p1, p2, p3]),
+            PathSegment::Close => {}
+        }
+    }
+    ts.map_points(&mut points);
+
+    let mut snap_x = vec![false; points.len()];
+    let mut snap_y = vec![false; points.len()];
+    let mut check_segment = |a: usize, b: usize| {
+        if (points[a].x - points[b].x).abs() < 0.001 {
+            snap_x[a] = true;
+            snap_x[b] = true;
+        }
+        if (points[a].y - points[b].y).abs() < 0.001 {
+            snap_y[a] = true;
+            snap_y[b] = true;
+        }
+    };
+
+    let mut idx = 0;
+    let mut contour_start = 0;
+    let mut prev = 0;
+    for segment in &segments {
+        match segment {
+            PathSegment::MoveTo(_) => {
+                contour_start = idx;
+                prev = idx;
+                idx += 1;
+            }
+            PathSegment::LineTo(_) => {
+                check_segment(prev, idx);
+                prev = idx;
+                idx += 1;
+            }
+            PathSegment::QuadTo(..) => {
+                prev = idx + 1;
+                idx += 2;
+            }
+            PathSegment::CubicTo(..) => {
+                prev = idx + 2;
+                idx += 3;
+            }
+            PathSegment::Close => check_segment(prev, contour_start),
+        }
+    }
+
+    let snap = |n: f32| (n - offset).round() + offset;
+    for (i, p) in points.iter_mut().enumerate() {
+        if snap_x[i] {
+            p.x = snap(p.x);
+        }
+        if snap_y[i] {
+            p.y = snap(p.y);
+        }
+    }
+
+    inv_ts.map_points(&mut points);
+
+    let mut builder = tiny_skia::PathBuilder::with_capacity(segments.len(), points.len());
+    let mut p = points.iter();
+    for segment in &segments {
+        match segment {
+            PathSegment::MoveTo(_) => {
+                let p1 = p.next()?;
+                builder.move_to(p1.x, p1.y);
+            }
+            PathSegment::LineTo(_) => {
+                let p1 = p.next()?;
+                builder.line_to(p1.x, p1.y);
+            }
+            PathSegment::QuadTo(..) => {
+                let (p1, p2) = (p.next()?, p.next()?);
+                builder.quad_to(p1.x, p1.y, p2.x, p2.y);
+            }
+            PathSegment::CubicTo(..) => {
+                let (p1, p2, p3) = (p.next()?, p.next()?, p.next()?);
+                builder.cubic_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y);
+            }
+            PathSegment::Close => builder.close(),
+        }
+    }
+
+    builder.finish()
+}
+
+/// Shifts the transform vertically so the text baseline would land on a pixel boundary.
+///
+/// Rotated and skewed text is left as is, since its baseline is not horizontal.
+fn snap_baseline(baseline: Option<f32>, ts: tiny_skia::Transform) -> tiny_skia::Transform {
+    let baseline = match baseline {
+        Some(v) => v,
+        None => return ts,
+    };
+
+    if ts.kx != 0.0 || ts.ky != 0.0 {
+        return ts;
+    }
+
+    let y = ts.sy * baseline + ts.ty;
+    ts.post_translate(0.0, y.round() - y)
+}
@@ -0,0 +1,98 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Mutex;
+
+/// A pool of reusable group layers.
+///
+/// Rendering a group with opacity, a clip path, a mask or filters requires
+/// a separate layer, which is allocated for each such group on each render.
+/// When set via [`RenderOptions::pixmap_pool`](crate::RenderOptions::pixmap_pool),
+/// layers are taken from the pool and returned to it after compositing,
+/// which amortizes allocations across multiple renders.
+///
+/// Layer buffers are grouped into size classes, each twice as large as the previous one.
+/// A pooled buffer is reused for any layer of the same class, so layers that differ
+/// by a few pixels between renders share memory. In exchange,
+/// a buffer can be up to twice as large as the layer it holds.
+///
+/// The pool can be shared between threads.
+pub struct PixmapPool {
+    max_bytes: usize,
+    buffers: Mutex<Vec<Vec<u8>>>,
+}
+
+impl PixmapPool {
+    /// Creates a new, empty pool.
+    ///
+    /// `max_bytes` is the maximum total size of buffers kept by the pool.
+    /// Layers that do not fit are deallocated as usual.
+    pub fn new(max_bytes: usize) -> Self {
+        PixmapPool {
+            max_bytes,
+            buffers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the total size of buffers currently kept by the pool.
+    pub fn pooled_bytes(&self) -> usize {
+        self.lock().iter().map(|b| b.capacity()).sum()
+    }
+
+    /// Deallocates all buffers kept by the pool.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// Returns a transparent pixmap, reusing a pooled buffer of the same size class when possible.
+    pub(crate) fn take(&self, width: u32, height: u32) -> Option<tiny_skia::Pixmap> {
+        let size = tiny_skia_path::IntSize::from_wh(width, height)?;
+        let len = (width as usize)
+            .checked_mul(height as usize)?
+            .checked_mul(tiny_skia::BYTES_PER_PIXEL)?;
+        let class = size_class(len)?;
+
+        let buffer = {
+            let mut buffers = self.lock();
+            buffers
+                .iter()
+                .position(|b| size_class(b.capacity()) == Some(class) && b.capacity() >= len)
+                .map(|idx| buffers.swap_remove(idx))
+        };
+
+        let mut buffer = buffer.unwrap_or_else(|| Vec::with_capacity(class));
+        buffer.clear();
+        buffer.resize(len, 0);
+        tiny_skia::Pixmap::from_vec(buffer, size)
+    }
+
+    /// Returns a pixmap buffer to the pool, unless the pool is full.
+    pub(crate) fn put(&self, pixmap: tiny_skia::Pixmap) {
+        let buffer = pixmap.take();
+        let mut buffers = self.lock();
+        let pooled: usize = buffers.iter().map(|b| b.capacity()).sum();
+        if pooled + buffer.capacity() <= self.max_bytes {
+            buffers.push(buffer);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Vec<u8>>> {
+        // A panic while holding the lock cannot leave buffers in an invalid state.
+        self.buffers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Rounds a buffer size up to the next power of two.
+fn size_class(len: usize) -> Option<usize> {
+    len.checked_next_power_of_two()
+}
+
+impl std::fmt::Debug for PixmapPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("PixmapPool")
+            .field("max_bytes", &self.max_bytes)
+            .field("pooled_bytes", &self.pooled_bytes())
+            .finish()
+    }
+}
@@ -2,13 +2,80 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use usvg::FuzzyEq;
+use usvg::{FuzzyEq, NodeExt};
 
 use crate::geom::{IntRect, IntSize, UsvgRectExt};
 use crate::tree::{ConvTransform, Group, Node, OptionLog, Tree};
+use crate::RenderOptions;
 
-pub struct Context {
+pub struct Context<'a> {
     pub max_bbox: IntRect,
+    pub opt: &'a RenderOptions,
+    /// Whether paths can be anti-aliased. Disabled for mask content
+    /// when `RenderOptions::mask_anti_alias` is not set.
+    pub anti_alias: bool,
+    /// A point in time after which rendering should be aborted.
+    pub deadline: Option<std::time::Instant>,
+    /// The number of groups being rendered.
+    pub depth: usize,
+    /// Per-node rendering time.
+    #[cfg(feature = "profiling")]
+    pub profile: &'a ProfileRecords,
+}
+
+#[cfg(feature = "profiling")]
+pub type ProfileRecords = std::cell::RefCell<Vec<(String, std::time::Duration)>>;
+
+impl Context<'_> {
+    /// Returns a transform that should be passed to the rasterizer.
+    pub fn raster_transform(&self, ts: tiny_skia::Transform) -> tiny_skia::Transform {
+        if !self.opt.deterministic {
+            return ts;
+        }
+
+        // 1/65536 is precise enough to not affect the output in any visible way,
+        // but coarse enough to hide floating-point discrepancies.
+        let round = |n: f32| (n * 65536.0).round() / 65536.0;
+        tiny_skia::Transform::from_row(
+            round(ts.sx),
+            round(ts.ky),
+            round(ts.kx),
+            round(ts.sy),
+            round(ts.tx),
+            round(ts.ty),
+        )
+    }
+
+    /// Checks that the rendering deadline has passed.
+    pub fn is_timed_out(&self) -> bool {
+        match self.deadline {
+            Some(deadline) => std::time::Instant::now() >= deadline,
+            None => false,
+        }
+    }
+
+    /// Returns an opacity set by `RenderOptions::opacity_override` for a node with the specified ID.
+    ///
+    /// Nodes without an ID are ignored.
+    pub fn opacity_override(&self, id: &str) -> Option<f32> {
+        if id.is_empty() {
+            return None;
+        }
+
+        let opacity = self.opt.opacity_override.as_ref()?.call(id)?;
+        Some(opacity.clamp(0.0, 1.0))
+    }
+
+    /// Records the time elapsed since `start` for an element with the specified ID.
+    ///
+    /// Elements without an ID are ignored.
+    #[cfg(feature = "profiling")]
+    pub fn record_time(&self, id: &str, start: std::time::Instant) {
+        if !id.is_empty() {
+            let elapsed = start.elapsed();
+            self.profile.borrow_mut().push((id.to_string(), elapsed));
+        }
+    }
 }
 
 impl Tree {
@@ -16,32 +83,1052 @@ impl Tree {
     ///
     /// `transform` will be used as a root transform.
     /// Can be used to position SVG inside the `pixmap`.
+    ///
+    /// Uses the default [`RenderOptions`].
     pub fn render(&self, transform: tiny_skia::Transform, pixmap: &mut tiny_skia::PixmapMut) {
+        self.render_with_options(&RenderOptions::default(), transform, pixmap)
+    }
+
+    /// Renders the tree onto the pixmap using the specified options.
+    ///
+    /// `transform` will be used as a root transform.
+    /// Can be used to position SVG inside the `pixmap`.
+    pub fn render_with_options(
+        &self,
+        opt: &RenderOptions,
+        transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) {
+        let transform = transform.pre_concat(self.view_box_transform());
+        // Cannot fail without a deadline.
+        let _ = self.render_impl(opt, None, transform, pixmap);
+    }
+
+    /// Renders the tree onto a custom canvas using the specified options.
+    ///
+    /// Produces the same drawing operations as [`Tree::render_with_options`],
+    /// but hands them over to `canvas` instead of a pixmap.
+    ///
+    /// [`RenderOptions::flatten_onto`], [`RenderOptions::linear_output`]
+    /// and [`RenderOptions::dither`] are ignored, since they operate on the final pixels.
+    ///
+    /// `transform` will be used as a root transform.
+    /// Can be used to position SVG inside the `canvas`.
+    pub fn render_to_canvas(
+        &self,
+        opt: &RenderOptions,
+        transform: tiny_skia::Transform,
+        canvas: &mut dyn crate::Canvas,
+    ) {
+        let transform = transform.pre_concat(self.view_box_transform());
+        // Cannot fail without a deadline.
+        let _ = self.render_canvas(opt, None, transform, canvas);
+    }
+
+    /// Renders the tree onto the pixmap, unless the deadline has passed.
+    ///
+    /// The deadline is checked before each top-level element and before each filter.
+    /// Nested SVG images are checked in the same way.
+    /// When it has passed, rendering is aborted and [`RenderError::Timeout`] is returned.
+    /// In which case `pixmap` will contain only the elements rendered so far.
+    ///
+    /// `transform` will be used as a root transform.
+    /// Can be used to position SVG inside the `pixmap`.
+    pub fn render_with_deadline(
+        &self,
+        opt: &RenderOptions,
+        deadline: std::time::Instant,
+        transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Result<(), RenderError> {
+        let transform = transform.pre_concat(self.view_box_transform());
+        self.render_impl(opt, Some(deadline), transform, pixmap)
+    }
+
+    /// Renders the tree onto the pixmap on top of a checkerboard pattern.
+    ///
+    /// The whole pixmap is filled with square `cell`-sized cells, starting from `c1`
+    /// in the top-left corner. Like image viewers do to indicate transparency.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Uses the default [`RenderOptions`].
+    pub fn render_with_checkerboard(
+        &self,
+        transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+        cell: u32,
+        c1: tiny_skia::Color,
+        c2: tiny_skia::Color,
+    ) {
+        // A cell larger than the pixmap covers it anyway.
+        let cell = cell.clamp(1, pixmap.width().max(pixmap.height()));
+        pixmap.fill(c1);
+
+        let mut paint = tiny_skia::Paint::default();
+        paint.set_color(c2);
+        paint.blend_mode = tiny_skia::BlendMode::Source;
+
+        // `div_ceil` requires Rust 1.73.
+        #[allow(clippy::manual_div_ceil)]
+        let (rows, cols) = (
+            (pixmap.height() + cell - 1) / cell,
+            (pixmap.width() + cell - 1) / cell,
+        );
+
+        let mut pb = tiny_skia::PathBuilder::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                if (row + col) % 2 == 1 {
+                    let (x, y, size) = ((col * cell) as f32, (row * cell) as f32, cell as f32);
+                    pb.push_rect(x, y, size, size);
+                }
+            }
+        }
+
+        if let Some(path) = pb.finish() {
+            pixmap.fill_path(
+                &path,
+                &paint,
+                tiny_skia::FillRule::Winding,
+                tiny_skia::Transform::default(),
+                None,
+            );
+        }
+
+        self.render(transform, pixmap);
+    }
+
+    /// Renders the tree onto the pixmap without fitting its view box into the pixmap.
+    ///
+    /// `transform` will be applied directly to the tree's user space,
+    /// so with an identity transform, one user unit equals one pixel.
+    ///
+    /// Uses the default [`RenderOptions`].
+    pub fn render_raw(&self, transform: tiny_skia::Transform, pixmap: &mut tiny_skia::PixmapMut) {
+        // Cannot fail without a deadline.
+        let _ = self.render_impl(&RenderOptions::default(), None, transform, pixmap);
+    }
+
+    /// Renders a region of the tree onto the whole pixmap.
+    ///
+    /// `view_rect` is in the tree's user space, like the view box is,
+    /// and is stretched to the pixmap size. Can be used to zoom into
+    /// and pan across an image without reparsing it.
+    ///
+    /// Uses the default [`RenderOptions`].
+    pub fn render_view(&self, view_rect: tiny_skia::Rect, pixmap: &mut tiny_skia::PixmapMut) {
+        if view_rect.width() <= 0.0 || view_rect.height() <= 0.0 {
+            return;
+        }
+
+        let transform = tiny_skia::Transform::from_row(
+            pixmap.width() as f32 / view_rect.width(),
+            0.0,
+            0.0,
+            pixmap.height() as f32 / view_rect.height(),
+            0.0,
+            0.0,
+        )
+        .pre_translate(-view_rect.x(), -view_rect.y());
+        self.render_raw(transform, pixmap);
+    }
+
+    /// Renders the tree onto the pixmap, clipped by the mask.
+    ///
+    /// The tree is rendered into a separate layer, which is then masked
+    /// and composited onto `pixmap`. Allows clipping an image to an arbitrary shape,
+    /// like a circle, without editing the SVG. Existing `pixmap` content is preserved.
+    ///
+    /// `clip` must have the same size as `pixmap`.
+    /// `transform` will be used as a root transform.
+    ///
+    /// Uses the default [`RenderOptions`].
+    ///
+    /// Returns `None` when mask and pixmap sizes do not match
+    /// or when a layer cannot be allocated.
+    pub fn render_masked(
+        &self,
+        clip: &tiny_skia::Mask,
+        transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Option<()> {
+        if clip.width() != pixmap.width() || clip.height() != pixmap.height() {
+            return None;
+        }
+
+        let mut layer = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height())?;
+        self.render(transform, &mut layer.as_mut());
+        layer.apply_mask(clip);
+
+        pixmap.draw_pixmap(
+            0,
+            0,
+            layer.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            tiny_skia::Transform::identity(),
+            None,
+        );
+
+        Some(())
+    }
+
+    /// Renders the tree onto the pixmap, tinted by a color matrix.
+    ///
+    /// `matrix` is a 4x5 row-major matrix, like the one of `feColorMatrix type="matrix"`,
+    /// and is applied to non-premultiplied sRGB colors of the whole rendered image.
+    /// Allows applying a tint, sepia or grayscale without editing the SVG.
+    ///
+    /// The tree is rendered into a separate layer, which is then tinted
+    /// and composited onto `pixmap`. Existing `pixmap` content is not affected.
+    /// Since transparent pixels are tinted as well, a matrix with a non-zero alpha offset
+    /// would fill the whole pixmap.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Uses the default [`RenderOptions`].
+    ///
+    /// Returns `None` when a layer cannot be allocated.
+    pub fn render_tinted(
+        &self,
+        matrix: [f32; 20],
+        transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Option<()> {
+        use rgb::FromSlice;
+
+        let mut layer = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height())?;
+        self.render(transform, &mut layer.as_mut());
+
+        let matrix = matrix.map(|n| n as f64);
+        let (width, height) = (layer.width(), layer.height());
+        let data = layer.data_mut().as_rgba_mut();
+        svgfilters::demultiply_alpha(data);
+        svgfilters::color_matrix(
+            svgfilters::ColorMatrix::Matrix(&matrix),
+            svgfilters::ImageRefMut::new(data, width, height),
+        );
+        svgfilters::multiply_alpha(data);
+
+        pixmap.draw_pixmap(
+            0,
+            0,
+            layer.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            tiny_skia::Transform::identity(),
+            None,
+        );
+
+        Some(())
+    }
+
+    fn render_impl(
+        &self,
+        opt: &RenderOptions,
+        deadline: Option<std::time::Instant>,
+        root_transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Result<(), RenderError> {
+        self.render_canvas(opt, deadline, root_transform, pixmap)?;
+
+        if let Some(color) = opt.flatten_onto {
+            flatten(color, pixmap);
+        }
+
+        if opt.linear_output {
+            into_linear_output(pixmap);
+        }
+
+        if opt.dither {
+            crate::dither::apply(pixmap);
+        }
+
+        Ok(())
+    }
+
+    fn render_canvas(
+        &self,
+        opt: &RenderOptions,
+        deadline: Option<std::time::Instant>,
+        root_transform: tiny_skia::Transform,
+        pixmap: &mut dyn crate::Canvas,
+    ) -> Result<(), RenderError> {
         let target_size = IntSize::new(pixmap.width(), pixmap.height()).unwrap();
-        let max_bbox = IntRect::new(
-            -(target_size.width() as i32) * 2,
-            -(target_size.height() as i32) * 2,
-            target_size.width() * 4,
-            target_size.height() * 4,
+        self.render_with_context(
+            &self.context(opt, deadline, target_size),
+            root_transform,
+            pixmap,
         )
-        .unwrap();
+    }
+
+    /// Renders the tree using a parent context.
+    ///
+    /// Used by nested SVG images, which must share the deadline and the recursion depth
+    /// of the parent tree and must not be affected by the final pixels processing.
+    pub(crate) fn render_with_context(
+        &self,
+        parent: &Context,
+        root_transform: tiny_skia::Transform,
+        pixmap: &mut dyn crate::Canvas,
+    ) -> Result<(), RenderError> {
+        let target_size = IntSize::new(pixmap.width(), pixmap.height()).unwrap();
+        let ctx = Context {
+            max_bbox: max_bbox(target_size),
+            #[cfg(feature = "profiling")]
+            profile: &self.profile,
+            ..*parent
+        };
+
+        #[cfg(feature = "profiling")]
+        self.profile.borrow_mut().clear();
+
+        // A zero scale collapses the image into a line or a point, so there is nothing to render.
+        let ts = root_transform;
+        let det = ts.sx * ts.sy - ts.kx * ts.ky;
+        let children: &[Node] = if det != 0.0 && det.is_finite() {
+            &self.children
+        } else {
+            &[]
+        };
+
+        for node in children {
+            if ctx.is_timed_out() {
+                return Err(RenderError::Timeout);
+            }
+
+            render_node(node, &ctx, root_transform, pixmap);
+        }
+
+        // A filter could have been skipped.
+        if ctx.is_timed_out() {
+            return Err(RenderError::Timeout);
+        }
+
+        if ctx.opt.draw_bboxes != crate::DebugBbox::None {
+            crate::debug::draw_bboxes(&self.children, &ctx, root_transform, pixmap);
+        }
+
+        Ok(())
+    }
+
+    fn context<'a>(
+        &'a self,
+        opt: &'a RenderOptions,
+        deadline: Option<std::time::Instant>,
+        target_size: IntSize,
+    ) -> Context<'a> {
+        Context {
+            max_bbox: max_bbox(target_size),
+            opt,
+            anti_alias: true,
+            deadline,
+            depth: 0,
+            #[cfg(feature = "profiling")]
+            profile: &self.profile,
+        }
+    }
+
+    /// Renders each top-level element into a separate pixmap.
+    ///
+    /// Each pixmap has the specified size and contains a single element
+    /// on a transparent background. Pixmaps are paired with element IDs, which can be empty.
+    /// Elements that produce no output, like empty groups, are skipped.
+    ///
+    /// Drawing the pixmaps in order on top of each other produces the same image
+    /// as [`Tree::render`], unless a top-level element has a non-normal blend mode,
+    /// which is applied against a transparent background instead.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Returns `None` when a pixmap cannot be allocated.
+    pub fn render_layers(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+    ) -> Option<Vec<(String, tiny_skia::Pixmap)>> {
+        let opt = RenderOptions::default();
+        let ctx = self.context(&opt, None, size);
+        let transform = transform.pre_concat(self.view_box_transform());
+
+        let mut layers = Vec::new();
+        for node in self.root.children() {
+            let (children, _) = crate::tree::convert_node(node.clone());
+            if children.is_empty() {
+                continue;
+            }
+
+            let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+            render_nodes(&children, &ctx, transform, &mut pixmap.as_mut());
+            layers.push((node.id().to_string(), pixmap));
+        }
+
+        Some(layers)
+    }
+
+    /// Returns the time spent on each element during the last render.
+    ///
+    /// Includes only elements with an ID. Groups include the time spent on their children.
+    /// Filters are recorded separately, using filter's ID.
+    /// Nested SVG images are not included.
+    ///
+    /// Records are in the order rendering of an element was finished.
+    /// Calling this method clears the records.
+    #[cfg(feature = "profiling")]
+    pub fn take_profile(&self) -> Vec<(String, std::time::Duration)> {
+        std::mem::take(&mut *self.profile.borrow_mut())
+    }
+
+    /// Renders the tree onto a new pixmap of the specified size.
+    ///
+    /// Unlike allocating a pixmap manually, checks that the pixmap fits
+    /// into [`RenderOptions::memory_budget`] first and reports allocation failures
+    /// instead of panicking.
+    ///
+    /// `transform` will be used as a root transform.
+    pub fn try_render_to_pixmap(
+        &self,
+        opt: &RenderOptions,
+        size: IntSize,
+        transform: tiny_skia::Transform,
+    ) -> Result<tiny_skia::Pixmap, RenderError> {
+        let bytes = (size.width() as usize)
+            .checked_mul(size.height() as usize)
+            .and_then(|n| n.checked_mul(tiny_skia::BYTES_PER_PIXEL))
+            .ok_or(RenderError::OutOfMemory)?;
+
+        if let Some(budget) = opt.memory_budget {
+            if bytes > budget {
+                return Err(RenderError::OutOfMemory);
+            }
+        }
+
+        let mut pixmap =
+            tiny_skia::Pixmap::new(size.width(), size.height()).ok_or(RenderError::OutOfMemory)?;
+        self.render_with_options(opt, transform, &mut pixmap.as_mut());
+        Ok(pixmap)
+    }
+
+    /// Renders the tree into a new pixmap, as large as possible,
+    /// but with no more than `max_pixels` pixels.
+    ///
+    /// The zoom is an integer when upscaling and a reciprocal of an integer when downscaling.
+    /// `transform` will be used as a root transform, before zooming.
+    ///
+    /// Uses the default [`RenderOptions`].
+    ///
+    /// Returns the applied zoom and the pixmap.
+    /// Returns `None` when even a single pixel doesn't fit the budget.
+    pub fn render_capped(
+        &self,
+        max_pixels: u64,
+        transform: tiny_skia::Transform,
+    ) -> Option<(f32, tiny_skia::Pixmap)> {
+        let (w, h) = (self.size.width(), self.size.height());
+        let fits = |zoom: f64| (w * zoom).ceil() * (h * zoom).ceil() <= max_pixels as f64;
+
+        let zoom = if fits(1.0) {
+            // The largest integer zoom that fits.
+            let (mut lo, mut hi) = (1.0, (max_pixels as f64 / (w * h)).sqrt().floor().max(1.0));
+            while lo < hi {
+                let mid = ((lo + hi) / 2.0).ceil();
+                if fits(mid) {
+                    lo = mid;
+                } else {
+                    hi = mid - 1.0;
+                }
+            }
+            lo
+        } else {
+            // The smallest integer divisor that fits.
+            // Dividing by the largest side always produces an 1x1 image.
+            let (mut lo, mut hi) = (1.0, w.max(h).ceil());
+            if !fits(1.0 / hi) {
+                return None;
+            }
+
+            while lo < hi {
+                let mid = ((lo + hi) / 2.0).floor();
+                if fits(1.0 / mid) {
+                    hi = mid;
+                } else {
+                    lo = mid + 1.0;
+                }
+            }
+            1.0 / lo
+        };
+
+        let size = IntSize::new((w * zoom).ceil() as u32, (h * zoom).ceil() as u32)?;
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+        let zoom = zoom as f32;
+        self.render(transform.post_scale(zoom, zoom), &mut pixmap.as_mut());
+
+        Some((zoom, pixmap))
+    }
+
+    /// Renders the tree and returns a fraction of pixels that are not fully transparent.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Returns `None` when a pixmap cannot be allocated.
+    pub fn coverage(&self, transform: tiny_skia::Transform, size: IntSize) -> Option<f32> {
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+        self.render(transform, &mut pixmap.as_mut());
+
+        let covered = pixmap.pixels().iter().filter(|p| p.alpha() > 0).count();
+        Some(covered as f32 / pixmap.pixels().len() as f32)
+    }
+
+    /// Renders the tree and counts pixels of each unique color.
+    ///
+    /// Colors are demultiplied RGBA. Fully transparent pixels are counted as `[0, 0, 0, 0]`.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Returns `None` when a pixmap cannot be allocated.
+    pub fn color_histogram(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+    ) -> Option<std::collections::HashMap<[u8; 4], u32>> {
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+        self.render(transform, &mut pixmap.as_mut());
+
+        let mut histogram = std::collections::HashMap::new();
+        for p in pixmap.pixels() {
+            let c = p.demultiply();
+            *histogram
+                .entry([c.red(), c.green(), c.blue(), c.alpha()])
+                .or_insert(0) += 1;
+        }
+
+        Some(histogram)
+    }
+
+    /// Renders the tree into a chain of mipmap levels.
+    ///
+    /// The first level has the `base_size` and each subsequent one is half the size
+    /// of the previous one, rounded up, down to 1x1. Only the first level is rendered.
+    /// Others are downsampled from the previous level using a box filter in linearRGB,
+    /// which is faster and more consistent than rendering each level separately.
+    ///
+    /// The tree is scaled to fill the `base_size`, ignoring its aspect ratio.
+    ///
+    /// Returns an empty list when a pixmap cannot be allocated.
+    pub fn render_mip_chain(&self, base_size: IntSize) -> Vec<tiny_skia::Pixmap> {
+        let mut levels = Vec::new();
+        let mut pixmap = match tiny_skia::Pixmap::new(base_size.width(), base_size.height()) {
+            Some(v) => v,
+            None => return levels,
+        };
+
+        let transform = tiny_skia::Transform::from_scale(
+            base_size.width() as f32 / self.size.width() as f32,
+            base_size.height() as f32 / self.size.height() as f32,
+        );
+        self.render(transform, &mut pixmap.as_mut());
+        levels.push(pixmap);
+
+        // Stops at 1x1, which cannot be downsampled any further.
+        while let Some(level) = levels
+            .last()
+            .and_then(|prev| crate::image::downscale_linear(prev, 0.5, 0.5))
+        {
+            levels.push(level);
+        }
+
+        levels
+    }
+
+    /// Checks that a point is covered by any visible element.
+    ///
+    /// Unlike a geometry test, accounts for clip paths, masks, opacity and filters,
+    /// therefore a point over a clipped out part of a shape is not visible.
+    /// Filters can sample only elements near the point.
+    ///
+    /// `point` is in canvas coordinates and `transform` will be used as a root transform,
+    /// just like during rendering.
+    pub fn is_visible_at(&self, point: tiny_skia::Point, transform: tiny_skia::Transform) -> bool {
+        let mut pixmap = match tiny_skia::Pixmap::new(1, 1) {
+            Some(v) => v,
+            None => return false,
+        };
+
+        // Move the point to the center of the only pixel.
+        let transform = transform.post_translate(0.5 - point.x, 0.5 - point.y);
+        self.render(transform, &mut pixmap.as_mut());
+        pixmap.pixels()[0].alpha() > 0
+    }
+
+    /// Renders the tree into a new texture of the specified size and format.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Rows are tightly packed, starting from the top one.
+    ///
+    /// Returns `None` when a texture cannot be allocated.
+    pub fn render_to_texture(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+        format: TextureFormat,
+    ) -> Option<Vec<u8>> {
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+        self.render(transform, &mut pixmap.as_mut());
+
+        let (swap_rb, premultiplied) = match format {
+            TextureFormat::Rgba8Premultiplied => (false, true),
+            TextureFormat::Rgba8 => (false, false),
+            TextureFormat::Bgra8Premultiplied => (true, true),
+            TextureFormat::Bgra8 => (true, false),
+        };
+
+        let mut data = pixmap.take();
+        if !premultiplied {
+            use rgb::FromSlice;
+            svgfilters::demultiply_alpha(data.as_rgba_mut());
+        }
+
+        if swap_rb {
+            for p in data.chunks_exact_mut(tiny_skia::BYTES_PER_PIXEL) {
+                p.swap(0, 2);
+            }
+        }
+
+        Some(data)
+    }
+
+    /// Renders the tree into a new premultiplied BGRA buffer of the specified size.
+    ///
+    /// Bytes of each pixel are stored in the blue, green, red, alpha order.
+    /// Which is a `0xAARRGGBB` `u32` on little-endian platforms
+    /// and matches 32-bit Windows GDI bitmaps.
+    ///
+    /// A shorthand for [`render_to_texture`](Self::render_to_texture)
+    /// with [`TextureFormat::Bgra8Premultiplied`].
+    ///
+    /// Returns `None` when a buffer cannot be allocated.
+    pub fn render_to_bgra_premul(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+    ) -> Option<Vec<u8>> {
+        self.render_to_texture(transform, size, TextureFormat::Bgra8Premultiplied)
+    }
+
+    /// Renders the tree and passes the result to `f` row by row.
+    ///
+    /// `f` receives a row index and premultiplied RGBA pixels of that row, from top to bottom.
+    /// Rows are borrowed from the rendered image, so nothing is copied.
+    /// Allows piping the result into an encoder of any format.
+    ///
+    /// The whole image is rendered at once, since filters and group layers can span
+    /// any number of rows. Therefore memory usage is the same as for [`Tree::render`].
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Returns `None` when a pixmap cannot be allocated.
+    pub fn render_scanlines(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+        mut f: impl FnMut(u32, &[u8]),
+    ) -> Option<()> {
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+        self.render(transform, &mut pixmap.as_mut());
+
+        let stride = size.width() as usize * tiny_skia::BYTES_PER_PIXEL;
+        for (y, row) in pixmap.data().chunks_exact(stride).enumerate() {
+            f(y as u32, row);
+        }
+
+        Some(())
+    }
+
+    /// Renders the tree into a new premultiplied RGBA buffer using Display P3 primaries.
+    ///
+    /// Colors in the SVG are assumed to be sRGB. The tree is rendered as usual
+    /// and then each pixel is converted into Display P3, which uses the same
+    /// transfer function, but wider primaries. So the result should be displayed
+    /// as a Display P3 image, otherwise colors will look desaturated.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Returns `None` when a buffer cannot be allocated.
+    pub fn render_p3(&self, transform: tiny_skia::Transform, size: IntSize) -> Option<Vec<u8>> {
+        let mut data = self.render_to_texture(transform, size, TextureFormat::Rgba8)?;
+        srgb_to_display_p3(&mut data);
+
+        for p in data.chunks_exact_mut(tiny_skia::BYTES_PER_PIXEL) {
+            let a = p[3] as u32;
+            for c in &mut p[0..3] {
+                *c = ((*c as u32 * a + 127) / 255) as u8;
+            }
+        }
+
+        Some(data)
+    }
+
+    /// Renders the tree and splits the result into separate channels.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Returns red, green, blue and alpha planes, each containing one byte per pixel.
+    /// Color channels are not premultiplied.
+    ///
+    /// Returns `None` when a pixmap cannot be allocated.
+    pub fn render_channels(
+        &self,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+    ) -> Option<[Vec<u8>; 4]> {
+        let data = self.render_to_texture(transform, size, TextureFormat::Rgba8)?;
+
+        let len = data.len() / tiny_skia::BYTES_PER_PIXEL;
+        let mut channels = [
+            Vec::with_capacity(len),
+            Vec::with_capacity(len),
+            Vec::with_capacity(len),
+            Vec::with_capacity(len),
+        ];
+        for p in data.chunks_exact(tiny_skia::BYTES_PER_PIXEL) {
+            for (channel, c) in channels.iter_mut().zip(p) {
+                channel.push(*c);
+            }
+        }
+
+        Some(channels)
+    }
+
+    /// Draws a previously rendered node under a new transform, without re-rendering it.
+    ///
+    /// `cached` must contain the node with the specified ID rendered via
+    /// [`Tree::from_usvg_node`], at any resolution.
+    /// It will be stretched over the node's bounding box and drawn on top of `pixmap`
+    /// using `transform` as a root transform. A group's blend mode is preserved.
+    ///
+    /// Useful for moving or zooming a static layer, since only the cached pixmap
+    /// is resampled. The rest of the tree is not rendered.
+    ///
+    /// Returns `None` when the node cannot be found or has a zero size.
+    pub fn render_transformed_group(
+        &self,
+        id: &str,
+        transform: tiny_skia::Transform,
+        cached: &tiny_skia::Pixmap,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Option<()> {
+        let node = self.root.descendants().find(|n| &*n.id() == id)?;
+        self.draw_cached_node(&node, transform, cached, pixmap)
+    }
+
+    /// Renders the node with the specified ID at each transform.
+    ///
+    /// The node is rendered only once, the same way as [`Tree::from_usvg_node`] would do,
+    /// and then drawn at each transform using [`Tree::render_transformed_group`].
+    /// The cached image resolution is chosen to match the largest scale among `transforms`.
+    ///
+    /// Each transform will be used as a root transform.
+    ///
+    /// Returns `None` when the node cannot be found or has a zero size.
+    pub fn render_instanced(
+        &self,
+        id: &str,
+        transforms: &[tiny_skia::Transform],
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Option<()> {
+        let node = self.root.descendants().find(|n| &*n.id() == id)?;
+        let tree = Tree::from_usvg_node(&node)?;
+
+        let view_box_ts = self.view_box_transform();
+        let scale = transforms
+            .iter()
+            .map(|ts| {
+                let ts = ts.pre_concat(view_box_ts);
+                ts.sx.hypot(ts.ky).max(ts.kx.hypot(ts.sy))
+            })
+            .fold(0.0, f32::max);
+
+        let (w, h) = (tree.size.width() as f32, tree.size.height() as f32);
+        let size = match IntSize::new((w * scale).ceil() as u32, (h * scale).ceil() as u32) {
+            Some(v) => v,
+            // Nothing to draw.
+            None => return Some(()),
+        };
+
+        let mut cached = tiny_skia::Pixmap::new(size.width(), size.height())?;
+        tree.render(
+            tiny_skia::Transform::from_scale(size.width() as f32 / w, size.height() as f32 / h),
+            &mut cached.as_mut(),
+        );
+
+        for ts in transforms {
+            self.draw_cached_node(&node, *ts, &cached, pixmap)?;
+        }
+
+        Some(())
+    }
+
+    /// Returns the corners of the bounding box of the node with the specified ID
+    /// in device coordinates.
+    ///
+    /// The bounding box is calculated in the node's own coordinates and then transformed
+    /// with all ancestor transforms, so the corners form a parallelogram
+    /// under rotation and skew. Corners are in the top-left, top-right, bottom-right
+    /// and bottom-left order, before transformation.
+    ///
+    /// `transform` will be used as a root transform.
+    ///
+    /// Returns `None` when the node cannot be found or has no bounding box.
+    pub fn node_quad(
+        &self,
+        id: &str,
+        transform: tiny_skia::Transform,
+    ) -> Option<[tiny_skia::Point; 4]> {
+        let node = self.root.descendants().find(|n| &*n.id() == id)?;
+        let bbox = node.calculate_local_bbox()?;
+
+        let (l, t, r, b) = (
+            bbox.left() as f32,
+            bbox.top() as f32,
+            bbox.right() as f32,
+            bbox.bottom() as f32,
+        );
+        let mut points = [
+            tiny_skia::Point::from_xy(l, t),
+            tiny_skia::Point::from_xy(r, t),
+            tiny_skia::Point::from_xy(r, b),
+            tiny_skia::Point::from_xy(l, b),
+        ];
+
+        let ts = transform
+            .pre_concat(self.view_box_transform())
+            .pre_concat(node.abs_transform().to_native());
+        ts.map_points(&mut points);
+
+        Some(points)
+    }
+
+    /// Renders the node with the specified ID into a new alpha mask.
+    ///
+    /// The node is rendered at its position in the tree, including ancestor transforms,
+    /// but without the rest of the tree. `transform` will be used as a root transform.
+    ///
+    /// Useful for building clipping stencils from arbitrary elements.
+    ///
+    /// Returns `None` when the node cannot be found, has a zero size
+    /// or when a mask cannot be allocated.
+    pub fn node_to_mask(
+        &self,
+        id: &str,
+        transform: tiny_skia::Transform,
+        size: IntSize,
+    ) -> Option<tiny_skia::Mask> {
+        let node = self.root.descendants().find(|n| &*n.id() == id)?;
+        let tree = Tree::from_usvg_node(&node)?;
+
+        let parent_ts = node
+            .parent()
+            .map(|p| p.abs_transform().to_native())
+            .unwrap_or_default();
+        let ts = transform
+            .pre_concat(self.view_box_transform())
+            .pre_concat(parent_ts);
+
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())?;
+        tree.render_raw(ts, &mut pixmap.as_mut());
+
+        Some(tiny_skia::Mask::from_pixmap(
+            pixmap.as_ref(),
+            tiny_skia::MaskType::Alpha,
+        ))
+    }
+
+    fn draw_cached_node(
+        &self,
+        node: &usvg::Node,
+        transform: tiny_skia::Transform,
+        cached: &tiny_skia::Pixmap,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) -> Option<()> {
+        let bbox = node.calculate_bbox().and_then(|r| r.to_rect())?;
+
+        let blend_mode = match *node.borrow() {
+            usvg::NodeKind::Group(ref g) => crate::tree::convert_blend_mode(g.blend_mode),
+            _ => tiny_skia::BlendMode::SourceOver,
+        };
+
+        let ts = transform
+            .pre_concat(self.view_box_transform())
+            .pre_translate(bbox.x() as f32, bbox.y() as f32)
+            .pre_scale(
+                bbox.width() as f32 / cached.width() as f32,
+                bbox.height() as f32 / cached.height() as f32,
+            );
+
+        let paint = tiny_skia::PixmapPaint {
+            opacity: 1.0,
+            blend_mode,
+            quality: tiny_skia::FilterQuality::Bicubic,
+        };
+        pixmap.draw_pixmap(0, 0, cached.as_ref(), &paint, ts, None);
+
+        Some(())
+    }
+}
+
+/// Converts demultiplied sRGB pixels into Display P3.
+fn srgb_to_display_p3(data: &mut [u8]) {
+    // Linear sRGB to linear Display P3. Both use the D65 white point.
+    const MATRIX: [[f32; 3]; 3] = [
+        [0.8224621, 0.177538, 0.0],
+        [0.0331941, 0.9668058, 0.0],
+        [0.0170827, 0.0723974, 0.9105199],
+    ];
+
+    let mut to_linear = [0.0; 256];
+    for (i, v) in to_linear.iter_mut().enumerate() {
+        *v = crate::paint_server::to_linear(i as f32 / 255.0);
+    }
+
+    for p in data.chunks_exact_mut(tiny_skia::BYTES_PER_PIXEL) {
+        if p[3] == 0 {
+            continue;
+        }
+
+        let rgb = [
+            to_linear[p[0] as usize],
+            to_linear[p[1] as usize],
+            to_linear[p[2] as usize],
+        ];
+        for (c, row) in p.iter_mut().zip(MATRIX.iter()) {
+            let v = row[0] * rgb[0] + row[1] * rgb[1] + row[2] * rgb[2];
+            // Display P3 uses the sRGB transfer function.
+            let v = crate::paint_server::from_linear(v.clamp(0.0, 1.0));
+            *c = (v * 255.0).round() as u8;
+        }
+    }
+}
+
+/// Converts premultiplied sRGB pixels into linear RGB.
+fn into_linear_output(pixmap: &mut tiny_skia::PixmapMut) {
+    use rgb::FromSlice;
+
+    let data = pixmap.data_mut().as_rgba_mut();
+    svgfilters::demultiply_alpha(data);
+    svgfilters::into_linear_rgb(data);
+    svgfilters::multiply_alpha(data);
+}
+
+/// Composites the pixmap onto a solid background and makes it fully opaque.
+fn flatten(color: tiny_skia::Color, pixmap: &mut tiny_skia::PixmapMut) {
+    let mut paint = tiny_skia::Paint::default();
+    paint.set_color(color);
+    paint.blend_mode = tiny_skia::BlendMode::DestinationOver;
 
-        let ts =
-            usvg::utils::view_box_to_transform(self.view_box.rect, self.view_box.aspect, self.size);
+    let rect = tiny_skia::Rect::from_xywh(0.0, 0.0, pixmap.width() as f32, pixmap.height() as f32);
+    if let Some(rect) = rect {
+        pixmap.fill_rect(rect, &paint, tiny_skia::Transform::default(), None);
+    }
+
+    // Color channels are premultiplied and cannot exceed alpha,
+    // so the pixel is still valid after making it opaque.
+    for p in pixmap
+        .data_mut()
+        .chunks_exact_mut(tiny_skia::BYTES_PER_PIXEL)
+    {
+        p[3] = 255;
+    }
+}
 
-        let root_transform = transform.pre_concat(ts.to_native());
+/// A texture pixel format.
+///
+/// Each pixel takes 4 bytes.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextureFormat {
+    Rgba8Premultiplied,
+    Rgba8,
+    Bgra8Premultiplied,
+    Bgra8,
+}
 
-        let ctx = Context { max_bbox: max_bbox };
+/// A rendering error.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenderError {
+    /// A pixmap cannot be allocated or doesn't fit into the memory budget.
+    OutOfMemory,
+    /// Rendering deadline has passed.
+    Timeout,
+}
 
-        render_nodes(&self.children, &ctx, root_transform, pixmap);
+impl std::fmt::Display for RenderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            RenderError::OutOfMemory => {
+                write!(f, "not enough memory to allocate a pixmap")
+            }
+            RenderError::Timeout => {
+                write!(f, "rendering deadline has passed")
+            }
+        }
     }
 }
 
+impl std::error::Error for RenderError {}
+
+/// Returns a region layers are clamped to. Which is 4x the canvas size.
+fn max_bbox(target_size: IntSize) -> IntRect {
+    IntRect::new(
+        -(target_size.width() as i32) * 2,
+        -(target_size.height() as i32) * 2,
+        target_size.width() * 4,
+        target_size.height() * 4,
+    )
+    .unwrap()
+}
+
+/// Renders a single path onto the pixmap.
+///
+/// The path is filled and stroked according to its style, including `paint-order`,
+/// exactly like it would be rendered as a part of a [`Tree`].
+/// Allows reusing the renderer for ad-hoc geometry without building a tree.
+///
+/// `transform` will be used as a root transform and is combined with the path's transform.
+///
+/// Options that affect the whole image, like [`RenderOptions::flatten_onto`],
+/// [`RenderOptions::linear_output`], [`RenderOptions::dither`]
+/// and [`RenderOptions::draw_bboxes`], are ignored.
+pub fn render_usvg_path(
+    path: &usvg::Path,
+    opt: &RenderOptions,
+    transform: tiny_skia::Transform,
+    pixmap: &mut dyn crate::Canvas,
+) {
+    let mut children = Vec::new();
+    crate::path::convert(path, &mut children);
+
+    #[cfg(feature = "profiling")]
+    let profile = ProfileRecords::default();
+
+    let target_size = IntSize::new(pixmap.width(), pixmap.height()).unwrap();
+    let ctx = Context {
+        max_bbox: max_bbox(target_size),
+        opt,
+        anti_alias: true,
+        deadline: None,
+        depth: 0,
+        #[cfg(feature = "profiling")]
+        profile: &profile,
+    };
+
+    render_nodes(&children, &ctx, transform, pixmap);
+}
+
 pub fn render_nodes(
     children: &[Node],
     ctx: &Context,
     transform: tiny_skia::Transform,
-    pixmap: &mut tiny_skia::PixmapMut,
+    pixmap: &mut dyn crate::Canvas,
 ) {
     for node in children {
         render_node(node, ctx, transform, pixmap);
@@ -52,13 +1139,26 @@ fn render_node(
     node: &Node,
     ctx: &Context,
     transform: tiny_skia::Transform,
-    pixmap: &mut tiny_skia::PixmapMut,
+    pixmap: &mut dyn crate::Canvas,
 ) {
+    #[cfg(feature = "profiling")]
+    let start = std::time::Instant::now();
+
     match node {
         Node::Group(ref group) => {
+            if let Some(ref filter) = ctx.opt.class_filter {
+                if !group.classes.is_empty() && !group.classes.iter().any(|c| filter.call(c)) {
+                    return;
+                }
+            }
+
             render_group(group, ctx, transform, pixmap);
         }
         Node::FillPath(ref path) => {
+            if !ctx.opt.render_fills {
+                return;
+            }
+
             crate::path::render_fill_path(
                 path,
                 tiny_skia::BlendMode::SourceOver,
@@ -68,6 +1168,10 @@ fn render_node(
             );
         }
         Node::StrokePath(ref path) => {
+            if !ctx.opt.render_strokes {
+                return;
+            }
+
             crate::path::render_stroke_path(
                 path,
                 tiny_skia::BlendMode::SourceOver,
@@ -77,25 +1181,46 @@ fn render_node(
             );
         }
         Node::Image(ref image) => {
-            crate::image::render_image(image, transform, pixmap);
+            crate::image::render_image(image, ctx, transform, pixmap);
+        }
+        Node::TextPlaceholder(ref placeholder) => {
+            crate::path::render_text_placeholder(placeholder, ctx, transform, pixmap);
         }
     }
+
+    #[cfg(feature = "profiling")]
+    ctx.record_time(node.id(), start);
 }
 
 fn render_group(
     group: &Group,
     ctx: &Context,
     transform: tiny_skia::Transform,
-    pixmap: &mut tiny_skia::PixmapMut,
+    pixmap: &mut dyn crate::Canvas,
 ) -> Option<()> {
     if group.bbox.fuzzy_eq(&usvg::PathBbox::new_bbox()) {
         log::warn!("Invalid group layer bbox detected.");
         return None;
     }
 
+    if ctx.depth >= ctx.opt.max_recursion_depth {
+        log::warn!("Maximum recursion depth reached. Skipping a group.");
+        return None;
+    }
+
+    let ctx = &Context {
+        depth: ctx.depth + 1,
+        ..*ctx
+    };
+
     let transform = transform.pre_concat(group.transform);
 
-    if group.is_transform_only() {
+    if ctx.opt.cull_offscreen && is_offscreen(&group.bbox, transform, pixmap) {
+        return Some(());
+    }
+
+    let opacity = ctx.opacity_override(&group.id).unwrap_or(group.opacity);
+    if group.is_transform_only() && opacity == 1.0 {
         render_nodes(&group.children, ctx, transform, pixmap);
         return Some(());
     }
@@ -119,8 +1244,12 @@ fn render_group(
         let bbox = bbox.to_rect()?.to_int_rect_round_out();
         // Make sure our filter region is not bigger than 4x the canvas size.
         // This is required mainly to prevent huge filter regions that would tank the performance.
-        // It should not affect the final result in any way.
-        bbox.fit_to_rect(ctx.max_bbox)
+        // It should not affect the final result in most cases.
+        if ctx.opt.clamp_filter_regions {
+            bbox.fit_to_rect(ctx.max_bbox)
+        } else {
+            bbox
+        }
     };
 
     // Make sure our layer is not bigger than 4x the canvas size.
@@ -143,8 +1272,12 @@ fn render_group(
 
     let transform = shift_ts.pre_concat(transform);
 
-    let mut sub_pixmap = tiny_skia::Pixmap::new(ibbox.width(), ibbox.height())
-        .log_none(|| log::warn!("Failed to allocate a group layer for: {:?}.", ibbox))?;
+    let sub_pixmap = match ctx.opt.pixmap_pool {
+        Some(ref pool) => pool.take(ibbox.width(), ibbox.height()),
+        None => tiny_skia::Pixmap::new(ibbox.width(), ibbox.height()),
+    };
+    let mut sub_pixmap =
+        sub_pixmap.log_none(|| log::warn!("Failed to allocate a group layer for: {:?}.", ibbox))?;
 
     render_nodes(&group.children, ctx, transform, &mut sub_pixmap.as_mut());
 
@@ -152,26 +1285,46 @@ fn render_group(
         let fill_paint = prepare_filter_paint(group.filter_fill.as_ref(), ctx, &sub_pixmap);
         let stroke_paint = prepare_filter_paint(group.filter_stroke.as_ref(), ctx, &sub_pixmap);
         for filter in &group.filters {
+            // Filters are the most expensive part of rendering.
+            if ctx.is_timed_out() {
+                if let Some(ref pool) = ctx.opt.pixmap_pool {
+                    pool.put(sub_pixmap);
+                }
+
+                return None;
+            }
+
+            #[cfg(feature = "profiling")]
+            let start = std::time::Instant::now();
+
             crate::filter::apply(
                 filter,
+                ctx,
                 transform,
                 fill_paint.as_ref(),
                 stroke_paint.as_ref(),
                 &mut sub_pixmap,
             );
+
+            #[cfg(feature = "profiling")]
+            ctx.record_time(&filter.id, start);
         }
     }
 
     if let Some(ref clip_path) = group.clip_path {
-        crate::clip::apply(clip_path, transform, &mut sub_pixmap);
+        crate::clip::apply(clip_path, ctx, transform, &mut sub_pixmap);
     }
 
     if let Some(ref mask) = group.mask {
         crate::mask::apply(mask, ctx, transform, &mut sub_pixmap);
     }
 
+    if let Some(ref callback) = ctx.opt.on_group_rendered {
+        callback.call(&group.id, &sub_pixmap, ibbox);
+    }
+
     let paint = tiny_skia::PixmapPaint {
-        opacity: group.opacity,
+        opacity,
         blend_mode: group.blend_mode,
         quality: tiny_skia::FilterQuality::Nearest,
     };
@@ -185,9 +1338,31 @@ fn render_group(
         None,
     );
 
+    if let Some(ref pool) = ctx.opt.pixmap_pool {
+        pool.put(sub_pixmap);
+    }
+
     Some(())
 }
 
+/// Checks that a layer bounding box doesn't intersect the pixmap.
+fn is_offscreen(
+    bbox: &usvg::PathBbox,
+    transform: tiny_skia::Transform,
+    pixmap: &dyn crate::Canvas,
+) -> bool {
+    let bbox = match bbox.transform(&usvg::Transform::from_native(transform)) {
+        Some(v) => v,
+        None => return false,
+    };
+
+    // Keep a 2px margin for anti-aliased pixels, like group layers do.
+    bbox.right() < -2.0
+        || bbox.bottom() < -2.0
+        || bbox.x() > pixmap.width() as f64 + 2.0
+        || bbox.y() > pixmap.height() as f64 + 2.0
+}
+
 /// Renders an image used by `FillPaint`/`StrokePaint` filter input.
 ///
 /// FillPaint/StrokePaint is mostly an undefined behavior and will produce different results
@@ -213,6 +1388,8 @@ fn prepare_filter_paint(
         paint: paint.clone(), // TODO: remove clone
         rule: tiny_skia::FillRule::Winding,
         anti_alias: true,
+        text_baseline: None,
+        id: String::new(),
         path: Rc::new(path),
     };
 
@@ -226,26 +1403,3 @@ fn prepare_filter_paint(
 
     Some(sub_pixmap)
 }
-
-pub trait TinySkiaPixmapMutExt {
-    fn create_rect_mask(
-        &self,
-        transform: tiny_skia::Transform,
-        rect: tiny_skia::Rect,
-    ) -> Option<tiny_skia::Mask>;
-}
-
-impl TinySkiaPixmapMutExt for tiny_skia::PixmapMut<'_> {
-    fn create_rect_mask(
-        &self,
-        transform: tiny_skia::Transform,
-        rect: tiny_skia::Rect,
-    ) -> Option<tiny_skia::Mask> {
-        let path = tiny_skia::PathBuilder::from_rect(rect);
-
-        let mut mask = tiny_skia::Mask::new(self.width(), self.height())?;
-        mask.fill_path(&path, tiny_skia::FillRule::Winding, true, transform);
-
-        Some(mask)
-    }
-}
@@ -0,0 +1,73 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::sync::Arc;
+
+use crate::{IntSize, PixmapPool, RenderOptions, Tree};
+
+/// The maximum total size of layers kept by a [`Renderer`] between renders.
+const MAX_POOLED_BYTES: usize = 256 * 1024 * 1024;
+
+/// A renderer that reuses its scratch buffers between renders.
+///
+/// [`Tree::render`] allocates group layers on each call, which dominates
+/// the rendering time of small images in hot loops and benchmarks.
+/// A renderer keeps those layers after rendering and reuses them
+/// as long as subsequent renders target a pixmap of the same size.
+/// Rendering to a pixmap of a different size deallocates them.
+///
+/// A renderer is not meant to be shared. Use a separate one per thread.
+#[derive(Debug)]
+pub struct Renderer {
+    opt: RenderOptions,
+    pool: Arc<PixmapPool>,
+    target_size: Option<IntSize>,
+}
+
+impl Renderer {
+    /// Creates a new renderer using the specified options.
+    ///
+    /// [`RenderOptions::pixmap_pool`] is ignored, since the renderer uses its own pool.
+    pub fn new(opt: RenderOptions) -> Self {
+        let pool = Arc::new(PixmapPool::new(MAX_POOLED_BYTES));
+        Renderer {
+            opt: RenderOptions {
+                pixmap_pool: Some(pool.clone()),
+                ..opt
+            },
+            pool,
+            target_size: None,
+        }
+    }
+
+    /// Returns rendering options.
+    pub fn options(&self) -> &RenderOptions {
+        &self.opt
+    }
+
+    /// Renders the tree onto the pixmap.
+    ///
+    /// Produces the same result as [`Tree::render_with_options`].
+    pub fn render(
+        &mut self,
+        tree: &Tree,
+        transform: tiny_skia::Transform,
+        pixmap: &mut tiny_skia::PixmapMut,
+    ) {
+        let target_size = IntSize::new(pixmap.width(), pixmap.height());
+        if self.target_size != target_size {
+            // Layers are sized relative to the target, so there is nothing to reuse.
+            self.pool.clear();
+            self.target_size = target_size;
+        }
+
+        tree.render_with_options(&self.opt, transform, pixmap);
+    }
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new(RenderOptions::default())
+    }
+}
@@ -0,0 +1,80 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+/*!
+Helpers for validating rendering results.
+
+Rendering results can slightly differ between platforms and optimization levels,
+therefore images should be compared with some tolerance.
+*/
+
+/// A difference between two images.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ImageDiff {
+    /// The maximum difference between two channels of the same pixel.
+    pub max_channel_diff: u8,
+    /// The mean difference over all channels of all pixels.
+    pub mean_diff: f64,
+    /// The number of pixels with at least one channel differing by more than the tolerance.
+    pub differing_pixels: usize,
+}
+
+impl ImageDiff {
+    /// Checks that images are identical.
+    pub fn is_identical(&self) -> bool {
+        self.max_channel_diff == 0
+    }
+}
+
+/// Compares two images.
+///
+/// Pixels are compared as is, channel by channel.
+/// Any channel difference marks a pixel as differing.
+///
+/// Returns `None` when images have different sizes.
+pub fn compare(a: &tiny_skia::Pixmap, b: &tiny_skia::Pixmap) -> Option<ImageDiff> {
+    compare_with_tolerance(a, b, 0)
+}
+
+/// Compares two images, ignoring channel differences up to `tolerance`.
+///
+/// `tolerance` affects only [`ImageDiff::differing_pixels`].
+///
+/// Returns `None` when images have different sizes.
+pub fn compare_with_tolerance(
+    a: &tiny_skia::Pixmap,
+    b: &tiny_skia::Pixmap,
+    tolerance: u8,
+) -> Option<ImageDiff> {
+    if a.width() != b.width() || a.height() != b.height() {
+        return None;
+    }
+
+    let mut max_channel_diff = 0;
+    let mut total_diff = 0u64;
+    let mut differing_pixels = 0;
+    for (p1, p2) in a
+        .data()
+        .chunks_exact(tiny_skia::BYTES_PER_PIXEL)
+        .zip(b.data().chunks_exact(tiny_skia::BYTES_PER_PIXEL))
+    {
+        let mut pixel_diff = 0;
+        for (c1, c2) in p1.iter().zip(p2) {
+            let d = c1.abs_diff(*c2);
+            total_diff += d as u64;
+            pixel_diff = pixel_diff.max(d);
+        }
+
+        max_channel_diff = max_channel_diff.max(pixel_diff);
+        if pixel_diff > tolerance {
+            differing_pixels += 1;
+        }
+    }
+
+    Some(ImageDiff {
+        max_channel_diff,
+        mean_diff: total_diff as f64 / a.data().len() as f64,
+        differing_pixels,
+    })
+}
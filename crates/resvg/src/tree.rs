@@ -8,9 +8,11 @@ use crate::clip::ClipPath;
 use crate::image::Image;
 use crate::mask::Mask;
 use crate::paint_server::Paint;
-use crate::path::{FillPath, StrokePath};
+use crate::path::{FillPath, StrokePath, TextPlaceholder};
 
 pub struct Group {
+    pub id: String,
+    pub classes: Vec<String>,
     pub transform: tiny_skia::Transform,
     pub opacity: f32,
     pub blend_mode: tiny_skia::BlendMode,
@@ -43,6 +45,20 @@ pub enum Node {
     FillPath(FillPath),
     StrokePath(StrokePath),
     Image(Image),
+    TextPlaceholder(TextPlaceholder),
+}
+
+impl Node {
+    #[cfg(feature = "profiling")]
+    pub fn id(&self) -> &str {
+        match self {
+            Node::Group(ref group) => &group.id,
+            Node::FillPath(ref path) => &path.id,
+            Node::StrokePath(ref path) => &path.id,
+            Node::Image(ref image) => &image.id,
+            Node::TextPlaceholder(_) => "",
+        }
+    }
 }
 
 // - No hidden nodes.
@@ -77,6 +93,12 @@ pub struct Tree {
     pub content_area: Option<usvg::PathBbox>,
 
     pub(crate) children: Vec<Node>,
+
+    /// The source node. Used to find nodes by ID.
+    pub(crate) root: usvg::Node,
+
+    #[cfg(feature = "profiling")]
+    pub(crate) profile: crate::render::ProfileRecords,
 }
 
 impl Tree {
@@ -96,6 +118,9 @@ impl Tree {
             view_box: tree.view_box,
             content_area: layer_bbox,
             children,
+            root: tree.root.clone(),
+            #[cfg(feature = "profiling")]
+            profile: Default::default(),
         }
     }
 
@@ -125,10 +150,135 @@ impl Tree {
             view_box: view_box,
             content_area: layer_bbox,
             children,
+            root: node.clone(),
+            #[cfg(feature = "profiling")]
+            profile: Default::default(),
         })
     }
 }
 
+impl Tree {
+    /// Checks that the tree has nothing to render.
+    ///
+    /// Hidden and zero-sized elements are already removed at this point.
+    pub fn is_empty(&self) -> bool {
+        !self.children.iter().any(is_drawable)
+    }
+
+    /// Checks that the tree has any filters.
+    ///
+    /// Includes filters inside masks and patterns.
+    pub fn has_filters(&self) -> bool {
+        self.children.iter().any(has_filters)
+    }
+
+    /// Calculates a union of all paths and images bounding boxes in user units.
+    ///
+    /// Unlike [`Tree::content_area`], strokes and filter regions are ignored
+    /// and the view box transform is not applied. Transforms of elements are still applied.
+    /// Useful for deriving a tight view box.
+    ///
+    /// Returns `None` when the tree has no geometry or when it has a zero width or height.
+    pub fn geometry_bbox(&self) -> Option<usvg::Rect> {
+        let mut bbox = usvg::PathBbox::new_bbox();
+        for node in self.root.descendants() {
+            let node_bbox = match *node.borrow() {
+                usvg::NodeKind::Path(ref path) => {
+                    path.data.bbox_with_transform(node.abs_transform(), None)
+                }
+                usvg::NodeKind::Image(ref image) => usvg::PathData::from_rect(image.view_box.rect)
+                    .bbox_with_transform(node.abs_transform(), None),
+                usvg::NodeKind::Group(_) | usvg::NodeKind::Text(_) => None,
+            };
+
+            if let Some(node_bbox) = node_bbox {
+                bbox = bbox.expand(node_bbox);
+            }
+        }
+
+        if bbox.fuzzy_eq(&usvg::PathBbox::new_bbox()) {
+            return None;
+        }
+
+        bbox.to_rect()
+    }
+}
+
+fn is_drawable(node: &Node) -> bool {
+    match node {
+        // Filters can produce an image even without children, like `feFlood`.
+        Node::Group(ref group) => {
+            !group.filters.is_empty() || group.children.iter().any(is_drawable)
+        }
+        Node::FillPath(_) | Node::StrokePath(_) => true,
+        Node::Image(ref image) => !matches!(image.kind, crate::image::ImageKind::Broken),
+        // Rendered only on request.
+        Node::TextPlaceholder(_) => false,
+    }
+}
+
+fn has_filters(node: &Node) -> bool {
+    match node {
+        Node::Group(ref group) => {
+            !group.filters.is_empty()
+                || group.children.iter().any(has_filters)
+                || mask_has_filters(group.mask.as_ref())
+        }
+        Node::FillPath(ref path) => paint_has_filters(&path.paint),
+        Node::StrokePath(ref path) => paint_has_filters(&path.paint),
+        Node::Image(_) | Node::TextPlaceholder(_) => false,
+    }
+}
+
+fn mask_has_filters(mask: Option<&Mask>) -> bool {
+    match mask {
+        Some(mask) => {
+            mask.children.iter().any(has_filters) || mask_has_filters(mask.mask.as_deref())
+        }
+        None => false,
+    }
+}
+
+fn paint_has_filters(paint: &Paint) -> bool {
+    match paint {
+        Paint::Pattern(ref pattern) => pattern.children.iter().any(has_filters),
+        Paint::Shader(_) => false,
+    }
+}
+
+impl Tree {
+    /// Calls `f` for each node of the tree, in rendering order,
+    /// with the node's transform composed with the transforms of all its ancestors.
+    ///
+    /// Transforms are composed the same way as during rendering,
+    /// including the view box transform, but without the root transform
+    /// passed to [`Tree::render`]. Which can be simply appended by the caller.
+    ///
+    /// Nothing is rasterized.
+    pub fn visit(&self, f: &mut dyn FnMut(&usvg::Node, tiny_skia::Transform)) {
+        visit_node(&self.root, self.view_box_transform(), f);
+    }
+
+    /// Returns a transform that fits the view box into the tree size.
+    pub(crate) fn view_box_transform(&self) -> tiny_skia::Transform {
+        usvg::utils::view_box_to_transform(self.view_box.rect, self.view_box.aspect, self.size)
+            .to_native()
+    }
+}
+
+fn visit_node(
+    node: &usvg::Node,
+    transform: tiny_skia::Transform,
+    f: &mut dyn FnMut(&usvg::Node, tiny_skia::Transform),
+) {
+    let transform = transform.pre_concat(node.transform().to_native());
+    f(node, transform);
+
+    for child in node.children() {
+        visit_node(&child, transform, f);
+    }
+}
+
 pub fn convert_node(node: usvg::Node) -> (Vec<Node>, Option<usvg::PathBbox>) {
     let mut children = Vec::new();
     let bboxes = convert_node_inner(node, &mut children);
@@ -176,7 +326,20 @@ fn convert_group(
     let mut group_children = Vec::new();
     let mut bboxes = match convert_children(node, &mut group_children) {
         Some(v) => v,
-        None => return convert_empty_group(ugroup, children),
+        None => {
+            // Only broken images, which have no bbox, can be left at this point.
+            // Keep them when the group doesn't require a layer.
+            if is_transform_only(ugroup) {
+                for node in group_children {
+                    if let Node::Image(mut image) = node {
+                        image.transform = ugroup.transform.to_native().pre_concat(image.transform);
+                        children.push(Node::Image(image));
+                    }
+                }
+            }
+
+            return convert_empty_group(ugroup, children);
+        }
     };
 
     let (filters, filter_bbox) =
@@ -205,6 +368,8 @@ fn convert_group(
     }
 
     let group = Group {
+        id: ugroup.id.clone(),
+        classes: ugroup.classes.clone(),
         transform: ugroup.transform.to_native(),
         opacity: ugroup.opacity.get() as f32,
         blend_mode: convert_blend_mode(ugroup.blend_mode),
@@ -226,6 +391,10 @@ fn convert_group(
     Some(bboxes)
 }
 
+fn is_transform_only(ugroup: &usvg::Group) -> bool {
+    !ugroup.needs_layer()
+}
+
 fn convert_empty_group(ugroup: &usvg::Group, children: &mut Vec<Node>) -> Option<BBoxes> {
     if ugroup.filters.is_empty() {
         return None;
@@ -247,6 +416,8 @@ fn convert_empty_group(ugroup: &usvg::Group, children: &mut Vec<Node>) -> Option
     }
 
     let group = Group {
+        id: ugroup.id.clone(),
+        classes: ugroup.classes.clone(),
         transform: ugroup.transform.to_native(),
         opacity: ugroup.opacity.get() as f32,
         blend_mode: convert_blend_mode(ugroup.blend_mode),
@@ -0,0 +1,2878 @@
+#![allow(clippy::field_reassign_with_default)]
+
+use usvg::{NodeExt, TreeParsing};
+
+fn render_str(svg: &str, opt: &resvg::RenderOptions) -> tiny_skia::Pixmap {
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::from_usvg(rtree.size);
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height()).unwrap();
+    rtree.render_with_options(opt, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap
+}
+
+fn turbulence_svg(seed: i32) -> String {
+    format!(
+        "<svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+            <filter id='filter1'>
+                <feTurbulence baseFrequency='0.05' seed='{}'/>
+            </filter>
+            <rect width='50' height='50' filter='url(#filter1)'/>
+        </svg>",
+        seed
+    )
+}
+
+#[test]
+fn turbulence_seed_offset() {
+    let opt = resvg::RenderOptions::default();
+    let base = render_str(&turbulence_svg(3), &opt);
+    assert_eq!(base.data(), render_str(&turbulence_svg(3), &opt).data());
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.turbulence_seed_offset = 2;
+    let shifted = render_str(&turbulence_svg(3), &opt);
+    assert_ne!(base.data(), shifted.data());
+
+    let expected = render_str(&turbulence_svg(5), &resvg::RenderOptions::default());
+    assert_eq!(expected.data(), shifted.data());
+}
+
+fn render_text(y: f32, opt: &resvg::RenderOptions) -> tiny_skia::Pixmap {
+    use usvg::TreeTextToPath;
+
+    let svg = format!(
+        "<svg viewBox='0 0 100 30' xmlns='http://www.w3.org/2000/svg'>
+            <text x='5' y='{}' font-family='Noto Sans' font-size='8'>Text</text>
+        </svg>",
+        y
+    );
+
+    let mut tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+    tree.convert_text(&crate::GLOBAL_FONTDB.lock().unwrap());
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(100, 30).unwrap();
+    rtree.render_with_options(opt, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap
+}
+
+#[test]
+fn snap_text_baseline() {
+    let mut opt = resvg::RenderOptions::default();
+    let expected = render_text(20.0, &opt);
+    assert_ne!(expected.data(), render_text(20.3, &opt).data());
+
+    opt.snap_text_baseline = true;
+    assert_eq!(expected.data(), render_text(20.3, &opt).data());
+    assert_eq!(expected.data(), render_text(19.8, &opt).data());
+}
+
+#[test]
+fn letter_spacing_does_not_drift() {
+    use usvg::TreeTextToPath;
+
+    // Returns the rightmost column with any ink.
+    let text_end = |letter_spacing: f32, scale: f32, opt: &resvg::RenderOptions| {
+        let svg = format!(
+            "<svg viewBox='0 0 200 20' xmlns='http://www.w3.org/2000/svg'>
+                <text x='5' y='15' font-family='Noto Sans' font-size='10'
+                      letter-spacing='{}'>iiiiiiiiiiiiiiiiiiiiiiiiiiiiii</text>
+            </svg>",
+            letter_spacing
+        );
+
+        let mut tree = usvg::Tree::from_str(&svg, &usvg::Options::default()).unwrap();
+        tree.convert_text(&crate::GLOBAL_FONTDB.lock().unwrap());
+        let rtree = resvg::Tree::from_usvg(&tree);
+
+        let (w, h) = ((200.0 * scale) as u32, (20.0 * scale) as u32);
+        let mut pixmap = tiny_skia::Pixmap::new(w, h).unwrap();
+        let ts = tiny_skia::Transform::from_scale(scale, scale);
+        rtree.render_with_options(opt, ts, &mut pixmap.as_mut());
+
+        (0..w)
+            .rev()
+            .find(|x| (0..h).any(|y| pixmap.pixel(*x, y).unwrap().alpha() > 0))
+            .unwrap() as f32
+    };
+
+    let mut opt = resvg::RenderOptions::default();
+    for snap in [false, true] {
+        opt.snap_text_baseline = snap;
+        for scale in [1.0, 1.7, 3.0] {
+            // The last glyph is shifted by the spacing of 29 previous ones.
+            let expected = 1.5 * 29.0 * scale;
+            let actual = text_end(1.5, scale, &opt) - text_end(0.0, scale, &opt);
+            assert!(
+                (actual - expected).abs() <= 1.0,
+                "scale {}: {} != {}",
+                scale,
+                actual,
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn render_only_fills_or_strokes() {
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='5' y='5' width='10' height='10' fill='#00FF00' stroke='#0000FF' stroke-width='2'/>
+    </svg>
+    ";
+
+    let center = |pixmap: &tiny_skia::Pixmap| pixmap.pixel(10, 10).unwrap();
+    let edge = |pixmap: &tiny_skia::Pixmap| pixmap.pixel(5, 10).unwrap();
+
+    let mut opt = resvg::RenderOptions::default();
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(center(&pixmap).green(), 255);
+    assert_eq!(edge(&pixmap).blue(), 255);
+
+    opt.render_strokes = false;
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(center(&pixmap).green(), 255);
+    assert_eq!(edge(&pixmap).blue(), 0);
+
+    opt.render_strokes = true;
+    opt.render_fills = false;
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(center(&pixmap).alpha(), 0);
+    assert_eq!(edge(&pixmap).blue(), 255);
+}
+
+#[test]
+fn deterministic_transform() {
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <circle cx='10' cy='10' r='7' fill='#336699'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.deterministic = true;
+
+    let render = |ts: tiny_skia::Transform| {
+        let mut pixmap = tiny_skia::Pixmap::new(20, 20).unwrap();
+        rtree.render_with_options(&opt, ts, &mut pixmap.as_mut());
+        pixmap
+    };
+
+    let ts1 = tiny_skia::Transform::from_row(0.9, 0.0, 0.0, 0.9, 0.3, 0.3);
+    let ts2 = tiny_skia::Transform::from_row(0.9000001, 0.0, 0.0, 0.9000001, 0.3000001, 0.3);
+    assert_eq!(render(ts1).data(), render(ts2).data());
+}
+
+#[cfg(feature = "color-management")]
+#[test]
+fn embedded_icc_profile() {
+    let svg = "<svg viewBox='0 0 4 4' xmlns='http://www.w3.org/2000/svg'>
+        <image width='4' height='4' href='image-display-p3.png'/>
+    </svg>";
+
+    let mut opt = usvg::Options::default();
+    opt.resources_dir = Some(std::path::PathBuf::from("tests/resources"));
+    let tree = usvg::Tree::from_str(svg, &opt).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(4, 4).unwrap();
+    rtree.render(tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    // A Display P3 rgb(200, 100, 50) is roughly rgb(215, 93, 31) in sRGB.
+    let c = pixmap.pixel(1, 1).unwrap();
+    assert!((c.red() as i32 - 215).abs() <= 2, "{:?}", c);
+    assert!((c.green() as i32 - 93).abs() <= 2, "{:?}", c);
+    assert!((c.blue() as i32 - 31).abs() <= 2, "{:?}", c);
+    assert_eq!(c.alpha(), 255);
+}
+
+#[test]
+fn try_render_to_pixmap_with_memory_budget() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let size = resvg::IntSize::new(10, 10).unwrap();
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.memory_budget = Some(10 * 10 * 4);
+    let pixmap = rtree
+        .try_render_to_pixmap(&opt, size, tiny_skia::Transform::default())
+        .unwrap();
+    assert_eq!(pixmap.pixel(5, 5).unwrap().green(), 128);
+
+    opt.memory_budget = Some(10 * 10 * 4 - 1);
+    let result = rtree.try_render_to_pixmap(&opt, size, tiny_skia::Transform::default());
+    assert_eq!(result.err(), Some(resvg::RenderError::OutOfMemory));
+}
+
+#[test]
+fn mask_anti_alias() {
+    let svg = "<svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <mask id='mask1'>
+            <circle cx='25' cy='25' r='20' fill='white'/>
+        </mask>
+        <rect width='50' height='50' fill='black' mask='url(#mask1)'/>
+    </svg>";
+
+    let is_edge = |a: u8| a != 0 && a != 255;
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert!(pixmap.pixels().iter().any(|p| is_edge(p.alpha())));
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.mask_anti_alias = false;
+    let pixmap = render_str(svg, &opt);
+    assert!(!pixmap.pixels().iter().any(|p| is_edge(p.alpha())));
+    assert_eq!(pixmap.pixel(25, 25).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(2, 2).unwrap().alpha(), 0);
+}
+
+#[test]
+fn render_atlas() {
+    let svg = "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <g id='red'><rect x='10' y='10' width='10' height='10' fill='#ff0000'/></g>
+        <g id='green'><rect x='50' y='50' width='10' height='20' fill='#00ff00'/></g>
+        <g id='blue'><rect x='80' y='0' width='10' height='10' fill='#0000ff'/></g>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(40, 40).unwrap();
+    let placements = rtree.render_atlas(
+        &["red", "green", "missing", "blue"],
+        tiny_skia::Transform::from_scale(2.0, 2.0),
+        &mut pixmap.as_mut(),
+    );
+
+    let rects: Vec<_> = placements
+        .iter()
+        .map(|(id, r)| (id.as_str(), r.x(), r.y(), r.width(), r.height()))
+        .collect();
+    // `blue` doesn't fit.
+    assert_eq!(rects, vec![("red", 20, 0, 20, 20), ("green", 0, 0, 20, 40)]);
+
+    let pixel = |x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+    assert_eq!(pixel(30, 10), (255, 0, 0, 255));
+    assert_eq!(pixel(10, 30), (0, 255, 0, 255));
+    assert_eq!(pixel(30, 30), (0, 0, 0, 0));
+}
+
+#[cfg(feature = "profiling")]
+#[test]
+fn profiling() {
+    let svg = "<svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feGaussianBlur stdDeviation='5'/>
+        </filter>
+        <g id='g1' opacity='0.5'>
+            <rect id='rect1' width='20' height='20'/>
+            <rect width='20' height='20'/>
+        </g>
+        <rect id='rect2' width='20' height='20' filter='url(#filter1)'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(50, 50).unwrap();
+    rtree.render(tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    let ids: Vec<_> = rtree.take_profile().into_iter().map(|(id, _)| id).collect();
+    assert_eq!(ids, vec!["rect1", "g1", "rect2", "filter1"]);
+    assert!(rtree.take_profile().is_empty());
+}
+
+#[test]
+fn render_to_texture() {
+    let svg = "<svg viewBox='0 0 1 1' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='1' height='1' fill='rgb(200, 100, 0)' fill-opacity='0.5'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let size = resvg::IntSize::new(1, 1).unwrap();
+
+    let render = |format| {
+        rtree
+            .render_to_texture(tiny_skia::Transform::default(), size, format)
+            .unwrap()
+    };
+
+    assert_eq!(
+        render(resvg::TextureFormat::Rgba8Premultiplied),
+        [100, 50, 0, 128]
+    );
+    assert_eq!(
+        render(resvg::TextureFormat::Bgra8Premultiplied),
+        [0, 50, 100, 128]
+    );
+    assert_eq!(render(resvg::TextureFormat::Rgba8), [199, 100, 0, 128]);
+    assert_eq!(render(resvg::TextureFormat::Bgra8), [0, 100, 199, 128]);
+}
+
+#[test]
+fn render_channels() {
+    let svg = "<svg viewBox='0 0 2 1' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='1' height='1' fill='rgb(200, 100, 0)' fill-opacity='0.5'/>
+        <rect x='1' width='1' height='1' fill='rgb(0, 50, 250)'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let size = resvg::IntSize::new(2, 1).unwrap();
+
+    let [r, g, b, a] = rtree
+        .render_channels(tiny_skia::Transform::default(), size)
+        .unwrap();
+    assert_eq!(r, [199, 0]);
+    assert_eq!(g, [100, 50]);
+    assert_eq!(b, [0, 250]);
+    assert_eq!(a, [128, 255]);
+}
+
+#[test]
+fn draw_bboxes() {
+    let svg = "<svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1' x='0' y='0' width='50' height='50' filterUnits='userSpaceOnUse'>
+            <feOffset dx='0'/>
+        </filter>
+        <g filter='url(#filter1)'>
+            <rect x='10.5' y='10.5' width='20' height='20' fill='#00ff00'/>
+        </g>
+    </svg>";
+
+    let pixel = |pixmap: &tiny_skia::Pixmap, x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue())
+    };
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert_eq!(pixel(&pixmap, 11, 20), (0, 255, 0));
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.draw_bboxes = resvg::DebugBbox::Nodes;
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(pixel(&pixmap, 10, 20), (255, 0, 0));
+    assert_eq!(pixel(&pixmap, 20, 20), (0, 255, 0));
+    assert_eq!(pixmap.pixel(0, 25).unwrap().alpha(), 0);
+
+    opt.draw_bboxes = resvg::DebugBbox::All;
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(pixel(&pixmap, 10, 20), (255, 0, 0));
+    let (r, _, b) = pixel(&pixmap, 0, 25);
+    assert!(r == 0 && b > 0);
+}
+
+#[test]
+fn broken_image_fill() {
+    // A PNG with a valid header, but invalid image data.
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <image x='5' y='5' width='10' height='10' href='data:image/png;base64,\
+            iVBORw0KGgoAAAANSUhEUgAAAAIAAAACCAIAAAD91JpzAAAAB0lEQVRnYXJiYWdliKMwNwAAAABJRU5ErkJggg=='/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.broken_image_fill = Some(tiny_skia::Color::from_rgba8(255, 0, 255, 255));
+    let pixmap = render_str(svg, &opt);
+    let c = pixmap.pixel(10, 10).unwrap();
+    assert_eq!(
+        (c.red(), c.green(), c.blue(), c.alpha()),
+        (255, 0, 255, 255)
+    );
+    assert_eq!(pixmap.pixel(2, 2).unwrap().alpha(), 0);
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    assert!(rtree.is_empty());
+    assert!(rtree.content_area.is_none());
+}
+
+#[test]
+fn background_image_fallback() {
+    // `enable-background` is not supported, like in browsers,
+    // therefore a backdrop blur is the same as a blur of the element itself.
+    let svg = |input: &str| {
+        format!(
+            "<svg viewBox='0 0 40 20' xmlns='http://www.w3.org/2000/svg'>
+                <filter id='filter1'>
+                    <feGaussianBlur in='{}' stdDeviation='2'/>
+                </filter>
+                <g enable-background='new'>
+                    <rect width='20' height='20' fill='green'/>
+                    <rect x='15' y='5' width='20' height='10' fill='blue' filter='url(#filter1)'/>
+                </g>
+            </svg>",
+            input
+        )
+    };
+
+    let opt = resvg::RenderOptions::default();
+    let backdrop = render_str(&svg("BackgroundImage"), &opt);
+    assert_eq!(
+        backdrop.data(),
+        render_str(&svg("SourceGraphic"), &opt).data()
+    );
+
+    // The green backdrop is not blurred into the free area.
+    let c = backdrop.pixel(25, 4).unwrap();
+    assert_eq!(c.green(), 0);
+    assert_ne!(c.blue(), 0);
+}
+
+#[test]
+fn compatibility_report() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feBlend in='BackgroundImage' in2='SourceGraphic'/>
+        </filter>
+        <pattern id='patt1' width='10' height='10' patternUnits='userSpaceOnUse'>
+            <image id='image1' width='10' height='10' href='data:image/png;base64,\
+                iVBORw0KGgoAAAANSUhEUgAAAAIAAAACCAIAAAD91JpzAAAAB0lEQVRnYXJiYWdliKMwNwAAAABJRU5ErkJggg=='/>
+        </pattern>
+        <rect width='10' height='10' filter='url(#filter1)'/>
+        <rect width='10' height='10' fill='url(#patt1)'/>
+        <rect width='10' height='10' fill='url(#patt1)'/>
+        <text id='text1' y='15'>Text</text>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    assert_eq!(
+        rtree.compatibility_report(),
+        vec![
+            resvg::Unsupported::BackgroundInput {
+                filter_id: "filter1".to_string()
+            },
+            resvg::Unsupported::UndecodableImage {
+                id: "image1".to_string()
+            },
+            resvg::Unsupported::Text {
+                id: "text1".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn compatibility_report_empty() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feGaussianBlur stdDeviation='2'/>
+        </filter>
+        <rect width='10' height='10' filter='url(#filter1)' style='mix-blend-mode:hue'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    assert!(rtree.compatibility_report().is_empty());
+}
+
+#[test]
+fn visit() {
+    let svg =
+        "<svg width='100' height='100' viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <g id='g1' transform='translate(10 20)'>
+            <rect id='rect1' width='10' height='10' transform='scale(2)'/>
+        </g>
+        <rect id='rect2' width='10' height='10'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut nodes = Vec::new();
+    rtree.visit(&mut |node, ts| {
+        if !node.id().is_empty() {
+            nodes.push((node.id().to_string(), ts));
+        }
+    });
+
+    assert_eq!(
+        nodes,
+        vec![
+            (
+                "g1".to_string(),
+                tiny_skia::Transform::from_row(2.0, 0.0, 0.0, 2.0, 20.0, 40.0)
+            ),
+            (
+                "rect1".to_string(),
+                tiny_skia::Transform::from_row(4.0, 0.0, 0.0, 4.0, 20.0, 40.0)
+            ),
+            (
+                "rect2".to_string(),
+                tiny_skia::Transform::from_row(2.0, 0.0, 0.0, 2.0, 0.0, 0.0)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn flood_opacity_in_subregion() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1' x='0' y='0' width='20' height='20' filterUnits='userSpaceOnUse'
+                primitiveUnits='userSpaceOnUse'>
+            <feFlood x='5' y='5' width='10' height='10' flood-color='red' flood-opacity='0.5'/>
+        </filter>
+        <rect width='20' height='20' filter='url(#filter1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    let pixel = |x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+
+    assert_eq!(pixel(10, 10), (128, 0, 0, 128));
+    assert_eq!(pixel(5, 5), (128, 0, 0, 128));
+    assert_eq!(pixel(4, 10), (0, 0, 0, 0));
+    assert_eq!(pixel(15, 15), (0, 0, 0, 0));
+}
+
+#[test]
+fn merge_order() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1' x='0' y='0' width='20' height='20' filterUnits='userSpaceOnUse'
+                primitiveUnits='userSpaceOnUse'>
+            <feFlood x='0' width='12' flood-color='red' result='red'/>
+            <feFlood x='4' width='12' flood-color='lime' result='green'/>
+            <feFlood x='8' width='12' flood-color='blue' result='blue'/>
+            <feMerge>
+                <feMergeNode in='red'/>
+                <feMergeNode in='green'/>
+                <feMergeNode in='blue'/>
+            </feMerge>
+        </filter>
+        <rect width='20' height='20' filter='url(#filter1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    let pixel = |x| {
+        let c = pixmap.pixel(x, 10).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+
+    assert_eq!(pixel(2), (255, 0, 0, 255));
+    assert_eq!(pixel(6), (0, 255, 0, 255));
+    assert_eq!(pixel(10), (0, 0, 255, 255));
+    assert_eq!(pixel(18), (0, 0, 255, 255));
+}
+
+#[test]
+fn clip_path_object_bounding_box_units() {
+    let svg = "<svg viewBox='0 0 40 20' xmlns='http://www.w3.org/2000/svg'>
+        <clipPath id='clip1' clipPathUnits='objectBoundingBox'>
+            <rect width='0.5' height='1'/>
+        </clipPath>
+        <rect x='10' y='5' width='20' height='10' fill='green' clip-path='url(#clip1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    let alpha = |x, y| pixmap.pixel(x, y).unwrap().alpha();
+
+    assert_eq!(alpha(10, 5), 255);
+    assert_eq!(alpha(19, 14), 255);
+    assert_eq!(alpha(20, 10), 0);
+    assert_eq!(alpha(29, 10), 0);
+    assert_eq!(alpha(15, 4), 0);
+    assert_eq!(alpha(15, 15), 0);
+}
+
+#[test]
+fn flatten_onto() {
+    let svg = "<svg viewBox='0 0 2 1' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='1' height='1' fill='black' fill-opacity='0.5'/>
+    </svg>";
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.flatten_onto = Some(tiny_skia::Color::WHITE);
+    let pixmap = render_str(svg, &opt);
+    let pixel = |x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+
+    assert_eq!(pixel(0, 0), (127, 127, 127, 255));
+    assert_eq!(pixel(1, 0), (255, 255, 255, 255));
+}
+
+#[test]
+fn flatten_onto_with_nested_image() {
+    // A 10x10 SVG with a red 5x5 rect in the top-left corner.
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='20' height='20' fill='blue'/>
+        <image x='10' y='10' width='10' height='10' href='data:image/svg+xml;base64,\
+            PHN2ZyB3aWR0aD0nMTAnIGhlaWdodD0nMTAnIHhtbG5zPSdodHRwOi8vd3d3LnczLm9yZy8yMDAwL3N2\
+            Zyc+PHJlY3Qgd2lkdGg9JzUnIGhlaWdodD0nNScgZmlsbD0ncmVkJy8+PC9zdmc+'/>
+    </svg>";
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.flatten_onto = Some(tiny_skia::Color::WHITE);
+    let pixmap = render_str(svg, &opt);
+    let pixel = |x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+
+    // The nested image must not be flattened on its own.
+    assert_eq!(pixel(5, 5), (0, 0, 255, 255));
+    assert_eq!(pixel(12, 12), (255, 0, 0, 255));
+    assert_eq!(pixel(17, 17), (0, 0, 255, 255));
+}
+
+#[test]
+fn render_transformed_group() {
+    let svg = "<svg viewBox='0 0 20 10' xmlns='http://www.w3.org/2000/svg'>
+        <g id='g1'>
+            <rect x='2' y='2' width='6' height='6' fill='green'/>
+        </g>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let node = tree.node_by_id("g1").unwrap();
+    let group_tree = resvg::Tree::from_usvg_node(&node).unwrap();
+    let mut cached = tiny_skia::Pixmap::new(12, 12).unwrap();
+    group_tree.render(
+        tiny_skia::Transform::from_scale(2.0, 2.0),
+        &mut cached.as_mut(),
+    );
+
+    let mut pixmap = tiny_skia::Pixmap::new(20, 10).unwrap();
+    rtree
+        .render_transformed_group(
+            "g1",
+            tiny_skia::Transform::from_translate(10.0, 0.0),
+            &cached,
+            &mut pixmap.as_mut(),
+        )
+        .unwrap();
+
+    let alpha = |x, y| pixmap.pixel(x, y).unwrap().alpha();
+    assert_eq!(alpha(5, 5), 0);
+    assert_eq!(alpha(15, 5), 255);
+    assert_eq!(alpha(11, 5), 0);
+    assert_eq!(alpha(18, 5), 0);
+
+    assert!(rtree
+        .render_transformed_group(
+            "missing",
+            tiny_skia::Transform::default(),
+            &cached,
+            &mut pixmap.as_mut()
+        )
+        .is_none());
+}
+
+#[test]
+fn visibility_hidden_affects_bbox_only() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <g id='g1'>
+            <rect width='5' height='5' fill='green'/>
+            <rect x='10' y='10' width='10' height='10' fill='green' visibility='hidden'/>
+        </g>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+
+    let bbox = tree
+        .node_by_id("g1")
+        .unwrap()
+        .calculate_bbox()
+        .and_then(|r| r.to_rect())
+        .unwrap();
+    assert_eq!(
+        (bbox.x(), bbox.y(), bbox.width(), bbox.height()),
+        (0.0, 0.0, 20.0, 20.0)
+    );
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert_eq!(pixmap.pixel(2, 2).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(15, 15).unwrap().alpha(), 0);
+}
+
+#[test]
+fn render_raw() {
+    let svg =
+        "<svg width='20' height='20' viewBox='10 10 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='10' y='10' width='10' height='10' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(30, 30).unwrap();
+    rtree.render_raw(tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    let alpha = |x, y| pixmap.pixel(x, y).unwrap().alpha();
+    assert_eq!(alpha(5, 5), 0);
+    assert_eq!(alpha(10, 10), 255);
+    assert_eq!(alpha(19, 19), 255);
+    assert_eq!(alpha(20, 20), 0);
+}
+
+#[test]
+fn tree_introspection() {
+    let check = |svg: &str| {
+        let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+        let rtree = resvg::Tree::from_usvg(&tree);
+        (rtree.is_empty(), rtree.has_filters())
+    };
+
+    let empty = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10' fill='none'/>
+        <g opacity='0.5'/>
+    </svg>";
+    assert_eq!(check(empty), (true, false));
+
+    let simple = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10'/>
+    </svg>";
+    assert_eq!(check(simple), (false, false));
+
+    let filter_only = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1' x='0' y='0' width='10' height='10' filterUnits='userSpaceOnUse'>
+            <feFlood flood-color='green'/>
+        </filter>
+        <g filter='url(#filter1)'/>
+    </svg>";
+    assert_eq!(check(filter_only), (false, true));
+
+    let filter_in_mask = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feGaussianBlur stdDeviation='1'/>
+        </filter>
+        <mask id='mask1'>
+            <rect width='10' height='10' fill='white' filter='url(#filter1)'/>
+        </mask>
+        <rect width='10' height='10' mask='url(#mask1)'/>
+    </svg>";
+    assert_eq!(check(filter_in_mask), (false, true));
+}
+
+#[test]
+fn render_capped() {
+    let svg = "<svg width='100' height='50' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='100' height='50' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let render = |max_pixels| {
+        rtree
+            .render_capped(max_pixels, tiny_skia::Transform::default())
+            .map(|(zoom, pixmap)| (zoom, pixmap.width(), pixmap.height()))
+    };
+
+    assert_eq!(render(20_000), Some((2.0, 200, 100)));
+    assert_eq!(render(19_999), Some((1.0, 100, 50)));
+    assert_eq!(render(1000), Some((1.0 / 3.0, 34, 17)));
+    assert_eq!(render(1), Some((0.01, 1, 1)));
+    assert_eq!(render(0), None);
+}
+
+#[test]
+fn coverage() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='5' height='4' fill='green' fill-opacity='0.1'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(10, 10).unwrap();
+    let coverage = rtree.coverage(tiny_skia::Transform::default(), size);
+    assert_eq!(coverage, Some(0.2));
+}
+
+#[test]
+fn radial_gradient_reflect_spread() {
+    let svg = "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <radialGradient id='rg1' cx='50' cy='50' r='10' gradientUnits='userSpaceOnUse'
+                        spreadMethod='reflect'>
+            <stop offset='0' stop-color='black'/>
+            <stop offset='1' stop-color='white'/>
+        </radialGradient>
+        <rect width='100' height='100' fill='url(#rg1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    let red = |x| pixmap.pixel(x, 50).unwrap().red();
+
+    // Bands are reflected every 10 units from the center.
+    assert!(red(50) < 20);
+    assert!(red(59) > 230);
+    assert!(red(70) < 20);
+    assert!(red(79) > 230);
+    assert!(red(90) < 20);
+    assert!(red(20) > 230);
+}
+
+#[test]
+fn render_instanced() {
+    let svg = "<svg viewBox='0 0 30 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' width='4' height='4' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(30, 10).unwrap();
+    let transforms = [
+        tiny_skia::Transform::from_translate(2.0, 2.0),
+        tiny_skia::Transform::from_translate(12.0, 2.0),
+        tiny_skia::Transform::from_translate(22.0, 2.0),
+    ];
+    rtree
+        .render_instanced("rect1", &transforms, &mut pixmap.as_mut())
+        .unwrap();
+
+    let alpha = |x, y| pixmap.pixel(x, y).unwrap().alpha();
+    assert_eq!(alpha(4, 4), 255);
+    assert_eq!(alpha(14, 4), 255);
+    assert_eq!(alpha(24, 4), 255);
+    assert_eq!(alpha(9, 4), 0);
+    assert_eq!(alpha(19, 4), 0);
+}
+
+#[test]
+fn stroke_miter_limit() {
+    // A 60 degrees angle has a miter ratio of 2 and a miter tip at y=18.
+    // A bevel will end at y=25.5.
+    let render = |join: &str, limit: f32| {
+        let svg = format!(
+            "<svg viewBox='0 0 100 100' width='100' height='100' \
+                  xmlns='http://www.w3.org/2000/svg'>
+                <path d='M 20 80 L 50 28.04 L 80 80' fill='none' stroke='black' \
+                      stroke-width='10' stroke-linejoin='{}' stroke-miterlimit='{}'/>
+            </svg>",
+            join, limit
+        );
+        let pixmap = render_str(&svg, &resvg::RenderOptions::default());
+        pixmap.pixel(50, 21).unwrap().alpha()
+    };
+
+    assert_eq!(render("miter", 2.1), 255);
+    assert_eq!(render("miter", 1.9), 0);
+    assert_eq!(render("bevel", 10.0), 0);
+    assert_eq!(render("round", 10.0), 0);
+}
+
+#[test]
+fn int_rect_conversions() {
+    use resvg::UsvgRectExt;
+
+    let rect = resvg::IntRect::new(1, 2, 3, 4).unwrap();
+    assert_eq!(
+        rect.to_skia_rect(),
+        tiny_skia::Rect::from_xywh(1.0, 2.0, 3.0, 4.0).unwrap()
+    );
+    assert_eq!(resvg::IntRect::from_skia(rect.to_skia().unwrap()), rect);
+    assert_eq!(resvg::IntRect::from_skia_rect(rect.to_skia_rect()), rect);
+
+    let rect = usvg::Rect::new(0.5, 0.5, 2.0, 2.0).unwrap();
+    assert_eq!(
+        rect.to_int_rect_round_out(),
+        resvg::IntRect::new(0, 0, 3, 3).unwrap()
+    );
+
+    let bounds = resvg::IntRect::new(0, 0, 10, 10).unwrap();
+    let rect = resvg::IntRect::new(-5, 5, 20, 20).unwrap();
+    assert_eq!(
+        rect.fit_to_rect(bounds),
+        resvg::IntRect::new(0, 5, 10, 5).unwrap()
+    );
+}
+
+#[cfg(feature = "encode-webp")]
+#[test]
+fn encode_webp() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10' fill='green' fill-opacity='0.5'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(10, 10).unwrap();
+    let data = rtree
+        .encode_webp(tiny_skia::Transform::default(), size, 90.0)
+        .unwrap();
+    assert_eq!(&data[0..4], b"RIFF");
+    assert_eq!(&data[8..12], b"WEBP");
+}
+
+#[cfg(feature = "encode-webp")]
+#[test]
+fn encode_webp_too_large() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(16384, 1).unwrap();
+    assert!(rtree
+        .encode_webp(tiny_skia::Transform::default(), size, 90.0)
+        .is_none());
+}
+
+#[cfg(feature = "encode-avif")]
+#[test]
+fn encode_avif() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10' fill='green' fill-opacity='0.5'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(10, 10).unwrap();
+    let data = rtree
+        .encode_avif(tiny_skia::Transform::default(), size, 90.0)
+        .unwrap();
+    assert_eq!(&data[4..12], b"ftypavif");
+}
+
+#[test]
+fn render_with_checkerboard() {
+    let svg = "<svg viewBox='0 0 8 8' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='6' y='6' width='2' height='2' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(8, 8).unwrap();
+    rtree.render_with_checkerboard(
+        tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+        3,
+        tiny_skia::Color::WHITE,
+        tiny_skia::Color::from_rgba8(204, 204, 204, 255),
+    );
+
+    let pixel = |x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+    assert_eq!(pixel(0, 0), (255, 255, 255, 255));
+    assert_eq!(pixel(3, 0), (204, 204, 204, 255));
+    assert_eq!(pixel(2, 3), (204, 204, 204, 255));
+    assert_eq!(pixel(3, 3), (255, 255, 255, 255));
+    assert_eq!(pixel(7, 7), (0, 128, 0, 255));
+}
+
+#[test]
+fn gaussian_blur_separate_std_dev() {
+    fn extents(std_dev: &str) -> (usize, usize) {
+        let svg = format!(
+            "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+                <filter id='filter1' x='-5' y='-5' width='11' height='11'>
+                    <feGaussianBlur stdDeviation='{}'/>
+                </filter>
+                <rect x='45' y='45' width='10' height='10' filter='url(#filter1)'/>
+            </svg>",
+            std_dev
+        );
+        let pixmap = render_str(&svg, &resvg::RenderOptions::default());
+        let alpha = |x: u32, y: u32| pixmap.pixel(x, y).unwrap().alpha();
+        let width = (0..100).filter(|x| alpha(*x, 50) > 0).count();
+        let height = (0..100).filter(|y| alpha(50, *y) > 0).count();
+        (width, height)
+    }
+
+    let (width, height) = extents("3 1");
+    assert!(width > height + 5, "{}x{}", width, height);
+
+    // A sigma too small for a box blur must still be applied
+    // when the other one is large.
+    let (width, height) = extents("6 0.5");
+    assert!(width > height + 20, "{}x{}", width, height);
+    assert!(height > 10, "{}x{}", width, height);
+
+    let (width, height) = extents("1 3");
+    assert!(height > width + 5, "{}x{}", width, height);
+}
+
+#[test]
+fn node_to_mask() {
+    let svg = "
+    <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='50' height='50' fill='green'/>
+        <g transform='translate(10 0)'>
+            <rect id='rect1' x='10' y='10' width='20' height='20' fill='black' fill-opacity='0.5'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(100, 100).unwrap();
+    let mask = rtree
+        .node_to_mask("rect1", tiny_skia::Transform::from_scale(2.0, 2.0), size)
+        .unwrap();
+    assert_eq!((mask.width(), mask.height()), (100, 100));
+
+    let value = |x: u32, y: u32| mask.data()[(y * mask.width() + x) as usize];
+    assert_eq!(value(50, 40), 128);
+    assert_eq!(value(30, 40), 0);
+    assert_eq!(value(70, 70), 0);
+
+    assert!(rtree
+        .node_to_mask("missing", tiny_skia::Transform::default(), size)
+        .is_none());
+}
+
+#[test]
+fn tiny_group() {
+    // A detailed group downscaled to a fraction of a pixel must still leave a trace.
+    let svg = "
+    <svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feOffset/>
+        </filter>
+        <g transform='translate(2 2) scale(0.001)' opacity='0.99'>
+            <rect width='400' height='400'/>
+            <circle cx='200' cy='600' r='200'/>
+        </g>
+        <g transform='translate(6 6) scale(0.001)' filter='url(#filter1)'>
+            <rect width='400' height='400'/>
+            <circle cx='200' cy='600' r='200'/>
+        </g>
+    </svg>
+    ";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert!(pixmap.pixel(2, 2).unwrap().alpha() > 0);
+    assert!(pixmap.pixel(6, 6).unwrap().alpha() > 0);
+}
+
+#[test]
+fn gradient_color_interpolation() {
+    fn middle(color_interpolation: &str) -> tiny_skia::ColorU8 {
+        let svg = format!(
+            "<svg viewBox='0 0 100 10' xmlns='http://www.w3.org/2000/svg'>
+                <linearGradient id='lg1' color-interpolation='{}'>
+                    <stop offset='0' stop-color='#ff0000'/>
+                    <stop offset='1' stop-color='#00ff00'/>
+                </linearGradient>
+                <rect width='100' height='10' fill='url(#lg1)'/>
+            </svg>",
+            color_interpolation
+        );
+        let pixmap = render_str(&svg, &resvg::RenderOptions::default());
+        pixmap.pixel(50, 5).unwrap().demultiply()
+    }
+
+    let c = middle("sRGB");
+    assert!((126..=130).contains(&c.red()), "{:?}", c);
+    assert!((126..=130).contains(&c.green()), "{:?}", c);
+
+    // 50% in linearRGB is ~188 in sRGB.
+    let c = middle("linearRGB");
+    assert!((185..=191).contains(&c.red()), "{:?}", c);
+    assert!((185..=191).contains(&c.green()), "{:?}", c);
+    assert_eq!(c.blue(), 0);
+
+    // `auto` falls back to sRGB.
+    let c = middle("auto");
+    assert!((126..=130).contains(&c.red()), "{:?}", c);
+}
+
+#[test]
+fn render_layers() {
+    let svg = "
+    <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <rect id='rect1' x='5' y='5' width='30' height='30' fill='green' fill-opacity='0.7'/>
+        <g id='g1' opacity='0.5'>
+            <circle cx='30' cy='30' r='15' fill='blue' stroke='black' stroke-width='3'/>
+        </g>
+        <g id='empty'/>
+        <path d='M 0 45 L 50 50' stroke='red'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(100, 100).unwrap();
+    let ts = tiny_skia::Transform::from_scale(2.0, 2.0);
+    let layers = rtree.render_layers(ts, size).unwrap();
+    let ids: Vec<_> = layers.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(ids, ["rect1", "g1", ""]);
+
+    let mut composed = tiny_skia::Pixmap::new(100, 100).unwrap();
+    for (_, layer) in &layers {
+        composed.draw_pixmap(
+            0,
+            0,
+            layer.as_ref(),
+            &tiny_skia::PixmapPaint::default(),
+            tiny_skia::Transform::default(),
+            None,
+        );
+    }
+
+    let mut expected = tiny_skia::Pixmap::new(100, 100).unwrap();
+    rtree.render(ts, &mut expected.as_mut());
+
+    for (a, b) in composed.data().iter().zip(expected.data()) {
+        assert!((*a as i16 - *b as i16).abs() <= 1);
+    }
+}
+
+#[test]
+fn arithmetic_composite_is_premultiplied() {
+    // Color channels bigger than alpha would produce over-bright halos.
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feFlood flood-color='white' flood-opacity='0.3'/>
+            <feComposite in2='SourceGraphic' operator='arithmetic' k1='2' k2='3' k3='0.5' k4='0.7'/>
+        </filter>
+        <rect x='5' y='5' width='10' height='10' fill='#ff8000' fill-opacity='0.4'
+              filter='url(#filter1)'/>
+    </svg>
+    ";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert!(pixmap.pixels().iter().any(|p| p.alpha() > 0));
+    for p in pixmap.pixels() {
+        assert!(p.red() <= p.alpha() && p.green() <= p.alpha() && p.blue() <= p.alpha());
+    }
+}
+
+#[test]
+fn rotated_image_edges() {
+    // A solid green 4x4 PNG.
+    let image = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+         xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image x='25' y='25' width='50' height='50' transform='rotate(30 50 50)'
+               xlink:href='data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAQAAAAECAIAAAAmkwkpAAAAD0lEQVR4nGNgaGBAIOI4ANA0CAF5jtVRAAAAAElFTkSuQmCC'/>
+    </svg>
+    ";
+    let rect = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='25' y='25' width='50' height='50' transform='rotate(30 50 50)' fill='#008000'/>
+    </svg>
+    ";
+
+    let image = render_str(image, &resvg::RenderOptions::default());
+    let rect = render_str(rect, &resvg::RenderOptions::default());
+
+    // Edges must be anti-aliased the same way as an identical path.
+    let partial = image
+        .pixels()
+        .iter()
+        .filter(|p| p.alpha() > 0 && p.alpha() < 255)
+        .count();
+    assert!(partial > 100, "{}", partial);
+    for (a, b) in image.pixels().iter().zip(rect.pixels()) {
+        assert!((a.alpha() as i16 - b.alpha() as i16).abs() <= 2);
+    }
+}
+
+#[test]
+fn debug_dump() {
+    let svg = "
+    <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <g transform='translate(10 0)'>
+            <rect width='10' height='10' fill='green' stroke='black'/>
+        </g>
+        <g opacity='0.5'>
+            <circle cx='25' cy='25' r='5' fill='blue'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let dump = rtree.debug_dump();
+
+    assert!(dump.starts_with("<tree size=\"50 50\" view-box=\"0 0 50 50\">\n"));
+    assert!(dump.ends_with("</tree>\n"));
+    assert!(dump.contains("<group transform=\"1 0 0 1 10 0\" bbox=\"10 0 10 10\" layer=\"false\">"));
+    assert!(dump.contains("<fill-path bbox=\"10 0 10 10\" paint=\"rgba(0 128 0 255)\""));
+    assert!(dump.contains("<stroke-path bbox=\"10 0 10 10\" paint=\"rgba(0 0 0 255)\" width=\"1\""));
+    assert!(dump.contains("layer=\"true\" opacity=\"0.5\">"));
+}
+
+#[test]
+fn pixmap_pool() {
+    let svg = "
+    <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <g opacity='0.5'>
+            <rect x='5' y='5' width='20' height='20' fill='green'/>
+            <g opacity='0.5'>
+                <circle cx='30' cy='30' r='15' fill='blue'/>
+            </g>
+        </g>
+    </svg>
+    ";
+
+    let expected = render_str(svg, &resvg::RenderOptions::default());
+
+    let pool = std::sync::Arc::new(resvg::PixmapPool::new(1024 * 1024));
+    let mut opt = resvg::RenderOptions::default();
+    opt.pixmap_pool = Some(pool.clone());
+    for _ in 0..3 {
+        assert_eq!(render_str(svg, &opt).data(), expected.data());
+    }
+
+    let pooled = pool.pooled_bytes();
+    assert!(pooled > 0);
+    // Buffers are reused, not accumulated.
+    render_str(svg, &opt);
+    assert_eq!(pool.pooled_bytes(), pooled);
+
+    pool.clear();
+    assert_eq!(pool.pooled_bytes(), 0);
+
+    // A pool that is too small keeps nothing.
+    let pool = std::sync::Arc::new(resvg::PixmapPool::new(16));
+    opt.pixmap_pool = Some(pool.clone());
+    assert_eq!(render_str(svg, &opt).data(), expected.data());
+    assert_eq!(pool.pooled_bytes(), 0);
+}
+
+#[test]
+fn pixmap_pool_size_classes() {
+    let svg = "
+    <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <g opacity='0.5'>
+            <rect x='5' y='5' width='20' height='20' fill='green'/>
+        </g>
+    </svg>
+    ";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let pool = std::sync::Arc::new(resvg::PixmapPool::new(usize::MAX));
+    let mut opt = resvg::RenderOptions::default();
+    opt.pixmap_pool = Some(pool.clone());
+
+    let mut pixmap = tiny_skia::Pixmap::new(50, 50).unwrap();
+    rtree.render_with_options(&opt, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    let pooled = pool.pooled_bytes();
+    assert!(pooled > 0);
+
+    // A slightly larger layer reuses the same buffer.
+    let mut expected = tiny_skia::Pixmap::new(50, 50).unwrap();
+    let ts = tiny_skia::Transform::from_scale(1.05, 1.05);
+    rtree.render(ts, &mut expected.as_mut());
+    pixmap.fill(tiny_skia::Color::TRANSPARENT);
+    rtree.render_with_options(&opt, ts, &mut pixmap.as_mut());
+    assert_eq!(pool.pooled_bytes(), pooled);
+    assert_eq!(pixmap.data(), expected.data());
+}
+
+#[test]
+fn pixmap_pool_with_deadline() {
+    let svg = "
+    <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feGaussianBlur stdDeviation='2'/>
+        </filter>
+        <g filter='url(#filter1)'>
+            <g id='g1' opacity='0.5'>
+                <rect x='5' y='5' width='20' height='20' fill='green'/>
+            </g>
+        </g>
+    </svg>
+    ";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let render = |deadline: std::time::Instant, wait: bool| {
+        let pool = std::sync::Arc::new(resvg::PixmapPool::new(usize::MAX));
+        let mut opt = resvg::RenderOptions::default();
+        opt.pixmap_pool = Some(pool.clone());
+        // Lets the deadline pass after the inner group and before the filter.
+        opt.on_group_rendered = Some(resvg::GroupRenderedCallback::new(move |id, _, _| {
+            if wait && id == "g1" {
+                while std::time::Instant::now() <= deadline {
+                    std::thread::sleep(std::time::Duration::from_millis(1));
+                }
+            }
+        }));
+
+        let mut pixmap = tiny_skia::Pixmap::new(50, 50).unwrap();
+        let result = rtree.render_with_deadline(
+            &opt,
+            deadline,
+            tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+        (result, pool.pooled_bytes())
+    };
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+    let (result, full_bytes) = render(deadline, false);
+    assert_eq!(result, Ok(()));
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_millis(10);
+    let (result, timed_out_bytes) = render(deadline, true);
+    assert_eq!(result, Err(resvg::RenderError::Timeout));
+    // The filtered layer is returned to the pool as well.
+    assert_eq!(timed_out_bytes, full_bytes);
+}
+
+#[test]
+fn cull_offscreen() {
+    let svg = "
+    <svg viewBox='0 0 50 50' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1' filterUnits='userSpaceOnUse' x='0' y='0' width='200' height='50'>
+            <feOffset dx='-100'/>
+        </filter>
+        <g opacity='0.5'>
+            <rect x='100' y='10' width='20' height='20' fill='green'/>
+        </g>
+        <g filter='url(#filter1)'>
+            <rect x='110' y='20' width='20' height='20' fill='blue'/>
+        </g>
+    </svg>
+    ";
+
+    let render = |cull: bool| {
+        let pool = std::sync::Arc::new(resvg::PixmapPool::new(usize::MAX));
+        let mut opt = resvg::RenderOptions::default();
+        opt.cull_offscreen = cull;
+        opt.pixmap_pool = Some(pool.clone());
+        let pixmap = render_str(svg, &opt);
+        (pixmap, pool.pooled_bytes())
+    };
+
+    let (culled, culled_bytes) = render(true);
+    let (full, full_bytes) = render(false);
+    assert_eq!(culled.data(), full.data());
+
+    // Only the filtered group was rendered, since its filter region is on the canvas.
+    assert_eq!(culled.pixel(15, 25).unwrap().blue(), 255);
+    assert!(culled_bytes > 0);
+    assert!(culled_bytes < full_bytes);
+}
+
+#[test]
+fn dither() {
+    let svg = "
+    <svg viewBox='0 0 256 40' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='#404040'/>
+            <stop offset='1' stop-color='#484848'/>
+        </linearGradient>
+        <rect width='256' height='20' fill='url(#lg1)'/>
+        <rect y='20' width='256' height='20' fill='#404040'/>
+    </svg>
+    ";
+
+    let plain = render_str(svg, &resvg::RenderOptions::default());
+    let mut opt = resvg::RenderOptions::default();
+    opt.dither = true;
+    let dithered = render_str(svg, &opt);
+
+    // Dithering shifts values only by a single step.
+    for (a, b) in plain.pixels().iter().zip(dithered.pixels()) {
+        assert!((a.red() as i16 - b.red() as i16).abs() <= 1);
+        assert_eq!(a.alpha(), b.alpha());
+    }
+
+    // The flat rect is untouched.
+    for x in 0..256 {
+        assert_eq!(dithered.pixel(x, 35).unwrap(), plain.pixel(x, 35).unwrap());
+    }
+
+    // Each band transition is replaced with a mix of neighbouring values.
+    let row = |pixmap: &tiny_skia::Pixmap| -> Vec<u8> {
+        (0..256)
+            .map(|x| pixmap.pixel(x, 5).unwrap().red())
+            .collect()
+    };
+    let transitions = |row: &[u8]| row.windows(2).filter(|w| w[0] != w[1]).count();
+    assert_eq!(transitions(&row(&plain)), 8);
+    assert!(transitions(&row(&dithered)) >= 8 * 5);
+
+    // The average brightness is preserved.
+    let sum = |pixmap: &tiny_skia::Pixmap| -> u32 { row(pixmap).iter().map(|v| *v as u32).sum() };
+    assert!((sum(&plain) as i32 - sum(&dithered) as i32).abs() < 64);
+}
+
+#[test]
+fn dither_with_nested_image() {
+    let direct = "
+    <svg viewBox='0 0 256 20' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='#404040'/>
+            <stop offset='1' stop-color='#484848'/>
+        </linearGradient>
+        <rect width='256' height='20' fill='url(#lg1)'/>
+    </svg>
+    ";
+    // The same SVG embedded as an image.
+    let nested = "<svg viewBox='0 0 256 20' xmlns='http://www.w3.org/2000/svg'>
+        <image width='256' height='20' href='data:image/svg+xml;base64,\
+            PHN2ZyB3aWR0aD0nMjU2JyBoZWlnaHQ9JzIwJyB4bWxucz0naHR0cDovL3d3dy53My5vcmcvMjAwMC9z\
+            dmcnPjxsaW5lYXJHcmFkaWVudCBpZD0nbGcxJz48c3RvcCBvZmZzZXQ9JzAnIHN0b3AtY29sb3I9JyM0\
+            MDQwNDAnLz48c3RvcCBvZmZzZXQ9JzEnIHN0b3AtY29sb3I9JyM0ODQ4NDgnLz48L2xpbmVhckdyYWRp\
+            ZW50PjxyZWN0IHdpZHRoPScyNTYnIGhlaWdodD0nMjAnIGZpbGw9J3VybCgjbGcxKScvPjwvc3ZnPg=='/>
+    </svg>";
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.dither = true;
+    // The nested image must be dithered only once.
+    assert_eq!(
+        render_str(direct, &opt).data(),
+        render_str(nested, &opt).data()
+    );
+}
+
+#[test]
+fn max_recursion_depth() {
+    let depth = 300;
+    let svg = format!(
+        "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>{}<rect width='20' height='20'/>{}</svg>",
+        "<g opacity='0.99'>".repeat(depth),
+        "</g>".repeat(depth),
+    );
+
+    let pixmap = render_str(&svg, &resvg::RenderOptions::default());
+    assert_ne!(pixmap.pixel(10, 10).unwrap().alpha(), 0);
+
+    let opt = resvg::RenderOptions {
+        max_recursion_depth: 100,
+        ..resvg::RenderOptions::default()
+    };
+    let pixmap = render_str(&svg, &opt);
+    assert_eq!(pixmap.pixel(10, 10).unwrap().alpha(), 0);
+}
+
+#[test]
+fn max_recursion_depth_with_nested_image() {
+    // A 20x20 SVG with a rect inside 60 nested groups.
+    let svg = format!(
+        "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>{}<image width='20' height='20' \
+            href='data:image/svg+xml;base64,\
+            PHN2ZyB3aWR0aD0nMjAnIGhlaWdodD0nMjAnIHhtbG5zPSdodHRwOi8vd3d3LnczLm9yZy8yMDAwL3N2\
+            Zyc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcg\
+            b3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0\
+            eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45\
+            OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcg\
+            b3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0\
+            eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45\
+            OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcg\
+            b3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0\
+            eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45\
+            OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcg\
+            b3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0\
+            eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45\
+            OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcg\
+            b3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0\
+            eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45\
+            OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcg\
+            b3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0\
+            eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45OSc+PGcgb3BhY2l0eT0nMC45\
+            OSc+PHJlY3Qgd2lkdGg9JzIwJyBoZWlnaHQ9JzIwJy8+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9n\
+            PjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9n\
+            PjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9n\
+            PjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9n\
+            PjwvZz48L2c+PC9nPjwvZz48L2c+PC9nPjwvZz48L2c+PC9zdmc+'/>{}</svg>",
+        "<g opacity='0.99'>".repeat(60),
+        "</g>".repeat(60),
+    );
+
+    let pixmap = render_str(&svg, &resvg::RenderOptions::default());
+    assert_ne!(pixmap.pixel(10, 10).unwrap().alpha(), 0);
+
+    // Groups of the nested image are counted as well.
+    let opt = resvg::RenderOptions {
+        max_recursion_depth: 100,
+        ..resvg::RenderOptions::default()
+    };
+    let pixmap = render_str(&svg, &opt);
+    assert_eq!(pixmap.pixel(10, 10).unwrap().alpha(), 0);
+}
+
+#[test]
+fn on_group_rendered() {
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <g id='g1' opacity='0.5'>
+            <rect x='5' y='5' width='10' height='10' fill='green'/>
+        </g>
+        <g id='g2'>
+            <rect width='5' height='5'/>
+        </g>
+    </svg>
+    ";
+
+    let groups = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let opt = resvg::RenderOptions {
+        on_group_rendered: Some(resvg::GroupRenderedCallback::new({
+            let groups = groups.clone();
+            move |id, pixmap, rect| {
+                let pixel = pixmap
+                    .pixel(10 - rect.x() as u32, 10 - rect.y() as u32)
+                    .unwrap();
+                groups
+                    .lock()
+                    .unwrap()
+                    .push((id.to_string(), rect, pixel.alpha()));
+            }
+        })),
+        ..resvg::RenderOptions::default()
+    };
+    render_str(svg, &opt);
+
+    // A transform-only group doesn't have a layer.
+    let groups = groups.lock().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0].0, "g1");
+    assert_eq!(groups[0].1, resvg::IntRect::new(3, 3, 14, 14).unwrap());
+    // The layer opacity is not applied yet.
+    assert_eq!(groups[0].2, 255);
+}
+
+#[test]
+fn paint_order_stroke() {
+    let svg = |paint_order: &str| {
+        format!(
+            "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+                <rect x='20' y='20' width='60' height='60' fill='#00ff00'
+                      stroke='#0000ff' stroke-width='20' paint-order='{}'/>
+            </svg>",
+            paint_order
+        )
+    };
+
+    // The inner half of the stroke covers the fill by default.
+    let pixmap = render_str(&svg("normal"), &resvg::RenderOptions::default());
+    assert_eq!(pixmap.pixel(25, 50).unwrap().blue(), 255);
+
+    let pixmap = render_str(&svg("stroke"), &resvg::RenderOptions::default());
+    assert_eq!(pixmap.pixel(25, 50).unwrap().green(), 255);
+    assert_eq!(pixmap.pixel(25, 50).unwrap().blue(), 0);
+    // The outer half is still visible.
+    assert_eq!(pixmap.pixel(15, 50).unwrap().blue(), 255);
+}
+
+#[test]
+fn gamma_correct_downscaling() {
+    // A 16x16 black and white checkerboard.
+    let svg = "<svg viewBox='0 0 4 4' xmlns='http://www.w3.org/2000/svg'>
+        <image width='4' height='4' href='data:image/png;base64,\
+            iVBORw0KGgoAAAANSUhEUgAAABAAAAAQCAAAAAA6mKC9AAAAEElEQVR42mNg+E8AMowoFQDA/X+Bqngu3AAAAABJRU5ErkJggg=='/>
+    </svg>";
+
+    let average = |pixmap: &tiny_skia::Pixmap| {
+        let sum: u32 = pixmap.pixels().iter().map(|p| p.red() as u32).sum();
+        sum / pixmap.pixels().len() as u32
+    };
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert!(average(&pixmap) < 160);
+
+    let opt = resvg::RenderOptions {
+        gamma_correct_downscaling: true,
+        ..resvg::RenderOptions::default()
+    };
+    let pixmap = render_str(svg, &opt);
+    assert!((185..=190).contains(&average(&pixmap)));
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 255));
+}
+
+#[test]
+fn render_view() {
+    let svg = "
+    <svg viewBox='0 0 200 100' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='100' y='50' width='10' height='10' fill='#00ff00'/>
+        <rect x='110' y='50' width='10' height='10' fill='#0000ff'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(40, 20).unwrap();
+    let view_rect = tiny_skia::Rect::from_xywh(100.0, 50.0, 20.0, 10.0).unwrap();
+    rtree.render_view(view_rect, &mut pixmap.as_mut());
+
+    // Each rect is zoomed to fill a half of the pixmap.
+    assert_eq!(pixmap.pixel(0, 0).unwrap().green(), 255);
+    assert_eq!(pixmap.pixel(19, 19).unwrap().green(), 255);
+    assert_eq!(pixmap.pixel(20, 0).unwrap().blue(), 255);
+    assert_eq!(pixmap.pixel(39, 19).unwrap().blue(), 255);
+}
+
+#[test]
+fn compare_images() {
+    let mut a = tiny_skia::Pixmap::new(2, 2).unwrap();
+    a.fill(tiny_skia::Color::from_rgba8(100, 100, 100, 255));
+    let mut b = a.clone();
+    assert!(resvg::testing::compare(&a, &b).unwrap().is_identical());
+
+    b.data_mut()[0] = 103;
+    b.data_mut()[5] = 101;
+    let diff = resvg::testing::compare(&a, &b).unwrap();
+    assert_eq!(diff.max_channel_diff, 3);
+    assert_eq!(diff.differing_pixels, 2);
+    assert_eq!(diff.mean_diff, 4.0 / 16.0);
+
+    let diff = resvg::testing::compare_with_tolerance(&a, &b, 1).unwrap();
+    assert_eq!(diff.differing_pixels, 1);
+
+    let c = tiny_skia::Pixmap::new(2, 1).unwrap();
+    assert!(resvg::testing::compare(&a, &c).is_none());
+}
+
+#[test]
+fn specular_lighting_exponent() {
+    let svg = |exponent: u32| {
+        format!(
+            "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+                <filter id='filter1' x='0' y='0' width='1' height='1'>
+                    <feSpecularLighting specularConstant='1' specularExponent='{}'
+                                        lighting-color='#ff8000'>
+                        <fePointLight x='50' y='50' z='30'/>
+                    </feSpecularLighting>
+                </filter>
+                <rect width='100' height='100' filter='url(#filter1)'/>
+            </svg>",
+            exponent
+        )
+    };
+
+    let spot_size =
+        |pixmap: &tiny_skia::Pixmap| pixmap.pixels().iter().filter(|p| p.alpha() > 128).count();
+
+    let wide = render_str(&svg(1), &resvg::RenderOptions::default());
+    let narrow = render_str(&svg(32), &resvg::RenderOptions::default());
+
+    // A light right above a flat surface is reflected at full intensity.
+    assert_eq!(wide.pixel(50, 50).unwrap().alpha(), 255);
+    assert_eq!(narrow.pixel(50, 50).unwrap().alpha(), 255);
+    assert!(spot_size(&narrow) * 4 < spot_size(&wide));
+    assert!(narrow.pixel(5, 5).unwrap().alpha() < 5);
+
+    // The alpha is the maximum color channel.
+    for p in wide.pixels() {
+        assert_eq!(p.alpha(), p.red());
+        assert!(p.green() <= p.red());
+    }
+}
+
+#[test]
+fn isolate_translucent_strokes() {
+    let svg = |stroke_width: f32| {
+        format!(
+            "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+                <path d='M 50 5 L 78 95 L 5 40 L 95 40 L 22 95 Z'
+                      fill='none' stroke='black' stroke-opacity='0.5' stroke-width='{}'/>
+            </svg>",
+            stroke_width
+        )
+    };
+
+    let max_alpha =
+        |pixmap: &tiny_skia::Pixmap| pixmap.pixels().iter().map(|p| p.alpha()).max().unwrap();
+
+    let opt = resvg::RenderOptions {
+        isolate_translucent_strokes: true,
+        ..resvg::RenderOptions::default()
+    };
+
+    // Self-intersections and corners are not darker than the rest of the stroke.
+    let pixmap = render_str(&svg(8.0), &opt);
+    assert_eq!(max_alpha(&pixmap), 128);
+
+    // Thin strokes are rasterized segment by segment.
+    let pixmap = render_str(&svg(1.0), &resvg::RenderOptions::default());
+    assert!(max_alpha(&pixmap) > 128);
+    let pixmap = render_str(&svg(1.0), &opt);
+    assert!(max_alpha(&pixmap) <= 128);
+}
+
+#[test]
+fn is_visible_at() {
+    let svg = "
+    <svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'>
+        <clipPath id='clip1'>
+            <rect width='50' height='100'/>
+        </clipPath>
+        <mask id='mask1'>
+            <rect width='100' height='50' fill='white'/>
+        </mask>
+        <g clip-path='url(#clip1)'>
+            <g mask='url(#mask1)'>
+                <rect width='100' height='100'/>
+            </g>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let ts = tiny_skia::Transform::default();
+    let point = tiny_skia::Point::from_xy;
+    assert!(rtree.is_visible_at(point(25.0, 25.0), ts));
+    // Clipped.
+    assert!(!rtree.is_visible_at(point(75.0, 25.0), ts));
+    // Masked.
+    assert!(!rtree.is_visible_at(point(25.0, 75.0), ts));
+    // The root transform is respected.
+    assert!(rtree.is_visible_at(
+        point(50.0, 50.0),
+        tiny_skia::Transform::from_scale(2.0, 2.0)
+    ));
+    assert!(!rtree.is_visible_at(
+        point(150.0, 50.0),
+        tiny_skia::Transform::from_scale(2.0, 2.0)
+    ));
+}
+
+#[test]
+fn render_to_bgra_premul() {
+    let svg = "
+    <svg viewBox='0 0 1 1' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='1' height='1' fill='rgb(255, 128, 0)' fill-opacity='0.5'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let size = resvg::IntSize::new(1, 1).unwrap();
+    let data = rtree
+        .render_to_bgra_premul(tiny_skia::Transform::default(), size)
+        .unwrap();
+
+    assert_eq!(data, [0, 64, 128, 128]);
+    assert_eq!(
+        u32::from_le_bytes([data[0], data[1], data[2], data[3]]),
+        0x80804000
+    );
+}
+
+#[test]
+fn smooth_upscale_limit() {
+    // A 4x4 black and white checkerboard scaled up 1000 times.
+    let svg = "<svg viewBox='0 0 4000 4000' xmlns='http://www.w3.org/2000/svg'>
+        <image width='4000' height='4000' href='data:image/png;base64,\
+            iVBORw0KGgoAAAANSUhEUgAAAAQAAAAECAAAAACMmsGiAAAAD0lEQVR42mNg+A+FDHAWAE/EB/lEbxExAAAAAElFTkSuQmCC'/>
+    </svg>";
+
+    let opt = resvg::RenderOptions {
+        smooth_upscale_limit: Some(16.0),
+        ..resvg::RenderOptions::default()
+    };
+
+    let start = std::time::Instant::now();
+    let pixmap = render_str(svg, &opt);
+    assert!(start.elapsed() < std::time::Duration::from_secs(30));
+
+    // Cell edges are sharp.
+    assert_eq!(pixmap.pixel(999, 100).unwrap().red(), 0);
+    assert_eq!(pixmap.pixel(1000, 100).unwrap().red(), 255);
+    assert_eq!(pixmap.pixel(1010, 100).unwrap().red(), 255);
+}
+
+#[test]
+fn class_filter() {
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <style>.screen { fill: green }</style>
+        <g class='layer print'>
+            <rect width='10' height='10'/>
+        </g>
+        <g class='layer screen'>
+            <rect x='10' width='10' height='10'/>
+        </g>
+        <rect y='10' width='10' height='10'/>
+    </svg>
+    ";
+
+    let opt = resvg::RenderOptions {
+        class_filter: Some(resvg::ClassFilter::new(|class| class == "print")),
+        ..resvg::RenderOptions::default()
+    };
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(15, 5).unwrap().alpha(), 0);
+    // Nodes outside of classified groups are always rendered.
+    assert_eq!(pixmap.pixel(5, 15).unwrap().alpha(), 255);
+
+    // CSS is still applied.
+    let opt = resvg::RenderOptions {
+        class_filter: Some(resvg::ClassFilter::new(|class| class == "screen")),
+        ..resvg::RenderOptions::default()
+    };
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+    assert_eq!(pixmap.pixel(15, 5).unwrap().green(), 128);
+}
+
+#[test]
+fn renderer() {
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <g opacity='0.5'>
+            <rect x='5' y='5' width='10' height='10' fill='green'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut renderer = resvg::Renderer::default();
+    for size in [20, 20, 40] {
+        let mut expected = tiny_skia::Pixmap::new(size, size).unwrap();
+        rtree.render(tiny_skia::Transform::default(), &mut expected.as_mut());
+
+        let mut pixmap = tiny_skia::Pixmap::new(size, size).unwrap();
+        renderer.render(
+            &rtree,
+            tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+        assert!(pixmap == expected);
+    }
+}
+
+#[test]
+fn marker_overflow() {
+    let svg = |overflow| {
+        format!(
+            "<svg viewBox='0 0 40 40' xmlns='http://www.w3.org/2000/svg'>
+                <marker id='marker1' refX='10' refY='10' markerWidth='20' markerHeight='20'
+                        markerUnits='userSpaceOnUse' {}>
+                    <rect x='-10' y='-10' width='40' height='40'/>
+                </marker>
+                <path d='M 20 20 L 30 20' stroke='none' marker-start='url(#marker1)'/>
+            </svg>",
+            overflow
+        )
+    };
+
+    let alpha = |overflow| {
+        let pixmap = render_str(&svg(overflow), &resvg::RenderOptions::default());
+        (
+            pixmap.pixel(15, 15).unwrap().alpha(),
+            pixmap.pixel(5, 5).unwrap().alpha(),
+        )
+    };
+
+    // Clipped to the marker viewport by default.
+    assert_eq!(alpha(""), (255, 0));
+    assert_eq!(alpha("overflow='hidden'"), (255, 0));
+    assert_eq!(alpha("overflow='visible'"), (255, 255));
+    assert_eq!(alpha("style='overflow:auto'"), (255, 255));
+}
+
+#[test]
+fn render_p3() {
+    let svg = "
+    <svg viewBox='0 0 2 1' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='1' height='1' fill='red'/>
+        <rect x='1' width='1' height='1' fill='white' fill-opacity='0.5'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let size = resvg::IntSize::new(2, 1).unwrap();
+    let data = rtree
+        .render_p3(tiny_skia::Transform::default(), size)
+        .unwrap();
+
+    // sRGB red is inside the Display P3 gamut, therefore is less saturated.
+    assert_eq!(&data[0..4], &[234, 51, 35, 255]);
+    // White is the same in both color spaces.
+    assert_eq!(&data[4..8], &[128, 128, 128, 128]);
+}
+
+#[test]
+fn clamp_filter_regions() {
+    // The shadow source is far outside of the canvas.
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1' x='-70' y='0' width='100' height='20' filterUnits='userSpaceOnUse'>
+            <feOffset dx='65'/>
+        </filter>
+        <rect x='-60' y='5' width='10' height='10' filter='url(#filter1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert_eq!(pixmap.pixel(10, 10).unwrap().alpha(), 0);
+
+    let opt = resvg::RenderOptions {
+        clamp_filter_regions: false,
+        ..resvg::RenderOptions::default()
+    };
+    let pixmap = render_str(svg, &opt);
+    assert_eq!(pixmap.pixel(5, 10).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(14, 10).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(16, 10).unwrap().alpha(), 0);
+}
+
+#[test]
+fn low_memory() {
+    let svg = "<svg viewBox='0 0 40 40' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feGaussianBlur in='SourceAlpha' stdDeviation='2' result='blur'/>
+            <feOffset dx='3' dy='3' result='offset'/>
+            <feFlood flood-color='green' flood-opacity='0.5'/>
+            <feComposite in2='offset' operator='in' result='shadow'/>
+            <feColorMatrix in='blur' type='hueRotate' values='30'/>
+            <feMerge>
+                <feMergeNode in='shadow'/>
+                <feMergeNode/>
+                <feMergeNode in='SourceGraphic'/>
+            </feMerge>
+            <feComponentTransfer>
+                <feFuncR type='linear' slope='0.5'/>
+            </feComponentTransfer>
+        </filter>
+        <rect x='10' y='10' width='15' height='15' fill='orange' filter='url(#filter1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    let opt = resvg::RenderOptions {
+        low_memory: true,
+        ..resvg::RenderOptions::default()
+    };
+    assert!(render_str(svg, &opt) == pixmap);
+    assert!(pixmap.pixel(20, 20).unwrap().alpha() > 0);
+}
+
+#[test]
+fn filter_clip_mask_opacity_order() {
+    let defs = "
+        <filter id='filter1' x='0' y='0' width='40' height='40' filterUnits='userSpaceOnUse'>
+            <feFlood flood-color='green'/>
+        </filter>
+        <clipPath id='clip1'>
+            <circle cx='20' cy='20' r='15'/>
+        </clipPath>
+        <mask id='mask1' maskUnits='userSpaceOnUse' x='0' y='0' width='40' height='40'>
+            <rect width='20' height='40' fill='white'/>
+        </mask>
+    ";
+
+    let combined = format!(
+        "<svg viewBox='0 0 40 40' xmlns='http://www.w3.org/2000/svg'>{}
+            <g filter='url(#filter1)' clip-path='url(#clip1)' mask='url(#mask1)' opacity='0.5'>
+                <rect x='15' y='15' width='10' height='10'/>
+            </g>
+        </svg>",
+        defs
+    );
+
+    // Filter, then clipping, then masking, then opacity.
+    let nested = format!(
+        "<svg viewBox='0 0 40 40' xmlns='http://www.w3.org/2000/svg'>{}
+            <g opacity='0.5'>
+                <g mask='url(#mask1)'>
+                    <g clip-path='url(#clip1)'>
+                        <g filter='url(#filter1)'>
+                            <rect x='15' y='15' width='10' height='10'/>
+                        </g>
+                    </g>
+                </g>
+            </g>
+        </svg>",
+        defs
+    );
+
+    let pixmap = render_str(&combined, &resvg::RenderOptions::default());
+    assert!(pixmap == render_str(&nested, &resvg::RenderOptions::default()));
+
+    let pixel = |x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+    // The flood is clipped and masked.
+    assert_eq!(pixel(10, 20), (0, 64, 0, 128));
+    assert_eq!(pixel(2, 2), (0, 0, 0, 0));
+    assert_eq!(pixel(30, 20), (0, 0, 0, 0));
+}
+
+#[test]
+fn node_quad() {
+    let svg = "
+    <svg width='40' height='40' viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <g transform='translate(10 0) rotate(90)'>
+            <rect id='rect1' x='2' y='2' width='6' height='4'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let quad = rtree
+        .node_quad("rect1", tiny_skia::Transform::from_translate(1.0, 0.0))
+        .unwrap();
+    let expected = [(17.0, 4.0), (17.0, 16.0), (9.0, 16.0), (9.0, 4.0)];
+    for (p, (x, y)) in quad.iter().zip(expected) {
+        assert!(
+            (p.x - x).abs() < 0.001 && (p.y - y).abs() < 0.001,
+            "{:?}",
+            quad
+        );
+    }
+
+    assert!(rtree
+        .node_quad("missing", tiny_skia::Transform::default())
+        .is_none());
+}
+
+#[test]
+fn rotated_repeating_gradient() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1' x2='5' gradientUnits='userSpaceOnUse'
+                        spreadMethod='repeat' gradientTransform='rotate(45)'>
+            <stop offset='0.5' stop-color='red'/>
+            <stop offset='0.5' stop-color='blue'/>
+        </linearGradient>
+        <rect width='20' height='20' fill='url(#lg1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    let red = |x, y| pixmap.pixel(x, y).unwrap().red() > 128;
+
+    // Stripes are perpendicular to the rotated gradient axis.
+    for sum in 0..39u32 {
+        let (x0, x1) = (sum.saturating_sub(19), sum.min(19));
+        let colors: Vec<bool> = (x0..=x1).map(|x| red(x, sum - x)).collect();
+        assert!(colors.iter().all(|c| *c == colors[0]));
+    }
+
+    // And are repeated along it.
+    let transitions = (1..20).filter(|x| red(*x, 0) != red(x - 1, 0)).count();
+    assert!(transitions >= 5);
+}
+
+#[test]
+fn missing_text_placeholder() {
+    use usvg::TreeTextToPath;
+
+    let svg = "<svg viewBox='0 0 100 20' xmlns='http://www.w3.org/2000/svg'>
+        <text x='5' y='15' font-family='Noto Sans' font-size='12'>AA\u{E000}\u{E000}\u{E000}</text>
+    </svg>";
+
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    tree.convert_text(&crate::GLOBAL_FONTDB.lock().unwrap());
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let render = |opt: &resvg::RenderOptions| {
+        let mut pixmap = tiny_skia::Pixmap::new(100, 20).unwrap();
+        rtree.render_with_options(opt, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap
+    };
+
+    let opt = resvg::RenderOptions {
+        missing_text_placeholder: Some(tiny_skia::Color::from_rgba8(255, 255, 0, 255)),
+        ..resvg::RenderOptions::default()
+    };
+    let yellow_columns = |pixmap: &tiny_skia::Pixmap| -> Vec<u32> {
+        (0..100)
+            .filter(|x| {
+                (0..20).any(|y| {
+                    let c = pixmap.pixel(*x, y).unwrap();
+                    (c.red(), c.green(), c.blue(), c.alpha()) == (255, 255, 0, 255)
+                })
+            })
+            .collect()
+    };
+
+    assert!(yellow_columns(&render(&resvg::RenderOptions::default())).is_empty());
+
+    // Only missing glyphs are highlighted.
+    let columns = yellow_columns(&render(&opt));
+    assert!(columns.len() > 10);
+    assert!(columns[0] > 15);
+}
+
+#[test]
+fn render_masked() {
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='20' height='20' fill='green'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let circle = tiny_skia::PathBuilder::from_circle(10.0, 10.0, 8.0).unwrap();
+    let mut clip = tiny_skia::Mask::new(20, 20).unwrap();
+    clip.fill_path(
+        &circle,
+        tiny_skia::FillRule::Winding,
+        true,
+        tiny_skia::Transform::default(),
+    );
+
+    let mut pixmap = tiny_skia::Pixmap::new(20, 20).unwrap();
+    pixmap.fill(tiny_skia::Color::WHITE);
+    rtree
+        .render_masked(&clip, tiny_skia::Transform::default(), &mut pixmap.as_mut())
+        .unwrap();
+
+    let pixel = |x, y| {
+        let c = pixmap.pixel(x, y).unwrap();
+        (c.red(), c.green(), c.blue(), c.alpha())
+    };
+    assert_eq!(pixel(10, 10), (0, 128, 0, 255));
+    // Outside of the circle the background is preserved.
+    assert_eq!(pixel(1, 1), (255, 255, 255, 255));
+
+    let small_clip = tiny_skia::Mask::new(10, 10).unwrap();
+    assert!(rtree
+        .render_masked(
+            &small_clip,
+            tiny_skia::Transform::default(),
+            &mut pixmap.as_mut()
+        )
+        .is_none());
+}
+
+#[test]
+fn mirrored_transform() {
+    let svg = "
+    <svg viewBox='0 0 40 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feOffset dx='4' dy='2' result='offset'/>
+            <feMerge>
+                <feMergeNode in='offset'/>
+                <feMergeNode in='SourceGraphic'/>
+            </feMerge>
+        </filter>
+        <mask id='mask1'>
+            <rect x='5' y='5' width='10' height='10' fill='white'/>
+        </mask>
+        <rect x='2' y='2' width='10' height='10' fill='green' filter='url(#filter1)'/>
+        <rect x='0' y='0' width='20' height='20' fill='blue' mask='url(#mask1)'/>
+        <g opacity='0.5'>
+            <rect x='25' y='5' width='10' height='10' fill='red'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut expected = tiny_skia::Pixmap::new(40, 20).unwrap();
+    rtree.render(tiny_skia::Transform::default(), &mut expected.as_mut());
+
+    let mut pixmap = tiny_skia::Pixmap::new(40, 20).unwrap();
+    rtree.render(
+        tiny_skia::Transform::from_row(-1.0, 0.0, 0.0, 1.0, 40.0, 0.0),
+        &mut pixmap.as_mut(),
+    );
+
+    let mut mirrored = tiny_skia::Pixmap::new(40, 20).unwrap();
+    for y in 0..20 {
+        for x in 0..40 {
+            let idx = (y * 40 + x) as usize;
+            mirrored.pixels_mut()[idx] = pixmap.pixel(39 - x, y).unwrap();
+        }
+    }
+
+    let diff = resvg::testing::compare(&expected, &mirrored).unwrap();
+    assert!(diff.is_identical(), "{:?}", diff);
+}
+
+#[test]
+fn zero_scale_transform() {
+    let svg = "
+    <svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1'>
+            <feTurbulence baseFrequency='0.1'/>
+            <feOffset dx='2'/>
+        </filter>
+        <mask id='mask1'>
+            <rect width='10' height='10' fill='white'/>
+        </mask>
+        <rect width='10' height='10' filter='url(#filter1)'/>
+        <rect width='20' height='20' mask='url(#mask1)' opacity='0.5'/>
+        <path d='M 0 0 L 20 20' stroke='black'/>
+    </svg>
+    ";
+
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    for ts in [
+        tiny_skia::Transform::from_scale(0.0, 0.0),
+        tiny_skia::Transform::from_scale(0.0, 1.0),
+        tiny_skia::Transform::from_scale(1.0, 0.0),
+    ] {
+        let mut pixmap = tiny_skia::Pixmap::new(20, 20).unwrap();
+        rtree.render(ts, &mut pixmap.as_mut());
+        assert!(pixmap.pixels().iter().all(|p| p.alpha() == 0));
+    }
+}
+
+#[test]
+fn color_histogram() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='5' height='10' fill='red'/>
+        <rect x='5' width='3' height='10' fill='blue' opacity='0.5'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(10, 10).unwrap();
+    let histogram = rtree
+        .color_histogram(tiny_skia::Transform::default(), size)
+        .unwrap();
+
+    assert_eq!(histogram.len(), 3);
+    assert_eq!(histogram[&[255, 0, 0, 255]], 50);
+    assert_eq!(histogram[&[0, 0, 0, 0]], 20);
+    assert_eq!(histogram.values().sum::<u32>(), 100);
+}
+
+#[test]
+fn tiled_feimage() {
+    let svg = "<svg viewBox='0 0 100 100' xmlns='http://www.w3.org/2000/svg'
+                    xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <defs>
+            <rect id='tile' x='2' y='2' width='5' height='5' fill='green'/>
+        </defs>
+        <filter id='filter1' filterUnits='userSpaceOnUse' x='0' y='0' width='100' height='100'>
+            <feImage xlink:href='#tile' x='0' y='0' width='10' height='10'/>
+            <feTile/>
+            <feComposite in2='SourceGraphic' operator='in'/>
+        </filter>
+        <rect x='10' y='10' width='80' height='80' filter='url(#filter1)'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let mut pixmap = tiny_skia::Pixmap::new(100, 100).unwrap();
+    rtree.render(tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    let green = tiny_skia::PremultipliedColorU8::from_rgba(0, 128, 0, 255).unwrap();
+    for y in 0..100 {
+        for x in 0..100 {
+            let inside = (10..90).contains(&x) && (10..90).contains(&y);
+            let in_tile = (2..7).contains(&(x % 10)) && (2..7).contains(&(y % 10));
+            let expected = if inside && in_tile {
+                green
+            } else {
+                tiny_skia::PremultipliedColorU8::TRANSPARENT
+            };
+            assert_eq!(pixmap.pixel(x, y), Some(expected), "{}x{}", x, y);
+        }
+    }
+}
+
+#[test]
+fn opacity_override() {
+    let svg = "<svg viewBox='0 0 30 10' xmlns='http://www.w3.org/2000/svg'>
+        <g id='g1'>
+            <rect width='10' height='10' fill='green'/>
+        </g>
+        <rect id='rect1' x='10' width='10' height='10' fill='green' fill-opacity='0.5'/>
+        <rect id='rect2' x='20' width='10' height='10' fill='green'/>
+    </svg>";
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.opacity_override = Some(resvg::OpacityOverride::new(|id| match id {
+        "g1" => Some(0.5),
+        "rect1" => Some(0.5),
+        "rect2" => Some(2.0),
+        _ => None,
+    }));
+    let pixmap = render_str(svg, &opt);
+
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 128);
+    assert_eq!(pixmap.pixel(15, 5).unwrap().alpha(), 64);
+    assert_eq!(pixmap.pixel(25, 5).unwrap().alpha(), 255);
+}
+
+#[test]
+fn integer_translated_image() {
+    // A 4x4 PNG, where pixel (x, y) is rgb(x * 60, y * 60, 200).
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <g transform='translate(1 0.5)'>
+            <image x='2' y='1.5' width='4' height='4' href='data:image/png;base64,\
+                iVBORw0KGgoAAAANSUhEUgAAAAQAAAAECAIAAAAmkwkpAAAAKUlEQVR4nA3HMQEAAAzCMIQhDGEVUVlbviSxc\
+                ZGY1NZV+pmdm+yDxSF4F/IXwWmvaVAAAAAASUVORK5CYII='/>
+        </g>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    for y in 0..10 {
+        for x in 0..10 {
+            let expected = if (3..7).contains(&x) && (2..6).contains(&y) {
+                let (r, g) = ((x - 3) as u8 * 60, (y - 2) as u8 * 60);
+                tiny_skia::PremultipliedColorU8::from_rgba(r, g, 200, 255).unwrap()
+            } else {
+                tiny_skia::PremultipliedColorU8::TRANSPARENT
+            };
+            assert_eq!(pixmap.pixel(x, y), Some(expected), "{}x{}", x, y);
+        }
+    }
+}
+
+#[test]
+fn source_alpha_shadow() {
+    let svg = "<svg viewBox='0 0 40 40' xmlns='http://www.w3.org/2000/svg'>
+        <filter id='filter1' x='-0.5' y='-0.5' width='3' height='3'>
+            <feGaussianBlur in='SourceAlpha' stdDeviation='2'/>
+            <feOffset dx='10' dy='10' result='offsetBlur'/>
+            <feFlood flood-color='black' flood-opacity='0.5'/>
+            <feComposite in2='offsetBlur' operator='in'/>
+            <feMerge>
+                <feMergeNode/>
+                <feMergeNode in='SourceGraphic'/>
+            </feMerge>
+        </filter>
+        <rect x='10' y='10' width='10' height='10' fill='red' filter='url(#filter1)'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+
+    // The shadow must not contain the source color.
+    let mut shadow_pixels = 0;
+    for y in 0..40 {
+        for x in 0..40 {
+            if (10..20).contains(&x) && (10..20).contains(&y) {
+                continue;
+            }
+
+            let p = pixmap.pixel(x, y).unwrap();
+            assert_eq!((p.red(), p.green(), p.blue()), (0, 0, 0), "{}x{}", x, y);
+            if p.alpha() > 0 {
+                shadow_pixels += 1;
+            }
+        }
+    }
+    assert!(shadow_pixels > 0);
+
+    // The shadow center is barely affected by the blur.
+    let alpha = pixmap.pixel(25, 25).unwrap().alpha();
+    assert!((120..=128).contains(&alpha), "{}", alpha);
+}
+
+#[test]
+fn pixel_snap() {
+    let svg = "<svg viewBox='0 0 40 40' xmlns='http://www.w3.org/2000/svg'>
+        <rect x='5.3' y='5.6' width='10.2' height='10' fill='green'/>
+        <rect x='20.3' y='20.6' width='10' height='10' fill='none' stroke='black'/>
+        <rect x='20.3' y='5.4' width='10' height='10' fill='none' stroke='black' stroke-width='2'/>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    assert!(pixmap
+        .pixels()
+        .iter()
+        .any(|p| p.alpha() != 0 && p.alpha() != 255));
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.pixel_snap = true;
+    let pixmap = render_str(svg, &opt);
+    for y in 0..40 {
+        for x in 0..40 {
+            // 1px strokes are rendered as hairlines, which have no joins.
+            if (x == 20 || x == 30) && (y == 20 || y == 30) {
+                continue;
+            }
+
+            let alpha = pixmap.pixel(x, y).unwrap().alpha();
+            assert!(alpha == 0 || alpha == 255, "{}x{}", x, y);
+        }
+    }
+
+    // The fill is snapped to pixel boundaries.
+    assert_eq!(pixmap.pixel(5, 6).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+    assert_eq!(pixmap.pixel(15, 15).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(16, 15).unwrap().alpha(), 0);
+
+    // A 1px stroke is snapped to pixel centers.
+    assert_eq!(pixmap.pixel(20, 25).unwrap().alpha(), 255);
+    assert_eq!(pixmap.pixel(19, 25).unwrap().alpha(), 0);
+    assert_eq!(pixmap.pixel(21, 25).unwrap().alpha(), 0);
+}
+
+#[test]
+fn render_scanlines() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect y='5' width='10' height='5' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let size = resvg::IntSize::new(10, 10).unwrap();
+    let mut data = Vec::new();
+    let mut rows = Vec::new();
+    rtree.render_scanlines(tiny_skia::Transform::default(), size, |y, row| {
+        assert_eq!(row.len(), 10 * 4);
+        rows.push(y);
+        data.extend_from_slice(row);
+    });
+
+    assert_eq!(rows, (0..10).collect::<Vec<_>>());
+    assert_eq!(&data[..4], &[0, 0, 0, 0]);
+    assert_eq!(&data[9 * 40..9 * 40 + 4], &[0, 128, 0, 255]);
+
+    let expected = render_str(svg, &resvg::RenderOptions::default());
+    assert_eq!(data, expected.data());
+}
+
+#[test]
+fn render_mip_chain() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='5' height='10' fill='white'/>
+        <rect x='5' width='5' height='10' fill='black'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let levels = rtree.render_mip_chain(resvg::IntSize::new(20, 5).unwrap());
+    let sizes: Vec<_> = levels.iter().map(|p| (p.width(), p.height())).collect();
+    assert_eq!(
+        sizes,
+        vec![(20, 5), (10, 3), (5, 2), (3, 1), (2, 1), (1, 1)]
+    );
+
+    // Averaging black and white in linearRGB produces a lighter gray than in sRGB.
+    let levels = rtree.render_mip_chain(resvg::IntSize::new(2, 2).unwrap());
+    assert_eq!(levels.len(), 2);
+    let c = levels[1].pixel(0, 0).unwrap();
+    assert_eq!(
+        (c.red(), c.green(), c.blue(), c.alpha()),
+        (188, 188, 188, 255)
+    );
+}
+
+#[test]
+fn render_tinted() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='5' height='10' fill='rgb(200, 100, 50)' fill-opacity='0.5'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    // Swaps red and blue.
+    #[rustfmt::skip]
+    let matrix = [
+        0.0, 0.0, 1.0, 0.0, 0.0,
+        0.0, 1.0, 0.0, 0.0, 0.0,
+        1.0, 0.0, 0.0, 0.0, 0.0,
+        0.0, 0.0, 0.0, 1.0, 0.0,
+    ];
+
+    let mut pixmap = tiny_skia::Pixmap::new(10, 10).unwrap();
+    pixmap.fill(tiny_skia::Color::from_rgba8(0, 0, 0, 255));
+    rtree
+        .render_tinted(
+            matrix,
+            tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        )
+        .unwrap();
+
+    let c = pixmap.pixel(2, 5).unwrap();
+    assert_eq!(
+        (c.red(), c.green(), c.blue(), c.alpha()),
+        (25, 50, 100, 255)
+    );
+    // The background is not tinted.
+    let c = pixmap.pixel(7, 5).unwrap();
+    assert_eq!((c.red(), c.green(), c.blue(), c.alpha()), (0, 0, 0, 255));
+}
+
+#[test]
+fn render_usvg_path() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <path d='M 2 2 L 18 5 L 10 18 Z' fill='green' stroke='black' stroke-width='2'
+              paint-order='stroke'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let transform = tiny_skia::Transform::from_rotate(5.0);
+    let mut expected = tiny_skia::Pixmap::new(20, 20).unwrap();
+    rtree.render(transform, &mut expected.as_mut());
+
+    let node = tree.root.first_child().unwrap();
+    let mut pixmap = tiny_skia::Pixmap::new(20, 20).unwrap();
+    match *node.borrow() {
+        usvg::NodeKind::Path(ref path) => resvg::render_usvg_path(
+            path,
+            &resvg::RenderOptions::default(),
+            transform,
+            &mut pixmap.as_mut(),
+        ),
+        _ => unreachable!(),
+    };
+
+    assert!(resvg::testing::compare(&pixmap, &expected)
+        .unwrap()
+        .is_identical());
+}
+
+#[test]
+fn nested_opacity() {
+    let svg = "<svg viewBox='0 0 30 10' xmlns='http://www.w3.org/2000/svg'>
+        <g opacity='0.5'>
+            <rect width='20' height='10' fill='black' opacity='0.5'/>
+            <rect x='10' width='20' height='10' fill='black' opacity='0.5'/>
+        </g>
+    </svg>";
+
+    let pixmap = render_str(svg, &resvg::RenderOptions::default());
+    // 0.5 * 0.5
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 64);
+    assert_eq!(pixmap.pixel(25, 5).unwrap().alpha(), 64);
+    // The overlap is 0.75 inside the group, which is then composited once with 0.5.
+    assert_eq!(pixmap.pixel(15, 5).unwrap().alpha(), 96);
+
+    // Paint opacity is multiplied the same way.
+    let svg = svg.replace("opacity='0.5'/>", "fill-opacity='0.5'/>");
+    let pixmap = render_str(&svg, &resvg::RenderOptions::default());
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 64);
+    assert_eq!(pixmap.pixel(15, 5).unwrap().alpha(), 96);
+}
+
+struct RecordingCanvas<'a> {
+    pixmap: tiny_skia::PixmapMut<'a>,
+    calls: Vec<&'static str>,
+}
+
+impl resvg::Canvas for RecordingCanvas<'_> {
+    fn width(&self) -> u32 {
+        self.pixmap.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.pixmap.height()
+    }
+
+    fn fill_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        fill_rule: tiny_skia::FillRule,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        self.calls.push("fill_path");
+        self.pixmap
+            .fill_path(path, paint, fill_rule, transform, mask);
+    }
+
+    fn stroke_path(
+        &mut self,
+        path: &tiny_skia::Path,
+        paint: &tiny_skia::Paint,
+        stroke: &tiny_skia::Stroke,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        self.calls.push("stroke_path");
+        self.pixmap
+            .stroke_path(path, paint, stroke, transform, mask);
+    }
+
+    fn fill_rect(
+        &mut self,
+        rect: tiny_skia::Rect,
+        paint: &tiny_skia::Paint,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        self.calls.push("fill_rect");
+        self.pixmap.fill_rect(rect, paint, transform, mask);
+    }
+
+    fn draw_pixmap(
+        &mut self,
+        x: i32,
+        y: i32,
+        pixmap: tiny_skia::PixmapRef,
+        paint: &tiny_skia::PixmapPaint,
+        transform: tiny_skia::Transform,
+        mask: Option<&tiny_skia::Mask>,
+    ) {
+        self.calls.push("draw_pixmap");
+        self.pixmap
+            .draw_pixmap(x, y, pixmap, paint, transform, mask);
+    }
+}
+
+#[test]
+fn render_to_canvas() {
+    let svg = "<svg viewBox='0 0 20 20' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10' fill='green'/>
+        <g opacity='0.5'>
+            <path d='M 2 18 L 18 2' stroke='black' stroke-width='2'/>
+        </g>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let opt = resvg::RenderOptions::default();
+    let transform = tiny_skia::Transform::default();
+    let mut expected = tiny_skia::Pixmap::new(20, 20).unwrap();
+    rtree.render_with_options(&opt, transform, &mut expected.as_mut());
+
+    let mut pixmap = tiny_skia::Pixmap::new(20, 20).unwrap();
+    let mut canvas = RecordingCanvas {
+        pixmap: pixmap.as_mut(),
+        calls: Vec::new(),
+    };
+    rtree.render_to_canvas(&opt, transform, &mut canvas);
+
+    // The stroke is drawn onto a group layer, which is then drawn onto the canvas.
+    assert_eq!(canvas.calls, ["fill_path", "draw_pixmap"]);
+    assert!(resvg::testing::compare(&pixmap, &expected)
+        .unwrap()
+        .is_identical());
+}
+
+#[test]
+fn linear_output() {
+    fn to_linear(c: u8) -> u8 {
+        let c = c as f32 / 255.0;
+        let v = if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        };
+        (v * 255.0).round() as u8
+    }
+
+    fn to_srgb(c: u8) -> u8 {
+        let c = c as f32 / 255.0;
+        let v = if c <= 0.0031308 {
+            c * 12.92
+        } else {
+            1.055 * c.powf(1.0 / 2.4) - 0.055
+        };
+        (v * 255.0).round() as u8
+    }
+
+    let svg = "<svg viewBox='0 0 256 1' xmlns='http://www.w3.org/2000/svg'>
+        <linearGradient id='lg1'>
+            <stop offset='0' stop-color='black'/>
+            <stop offset='1' stop-color='white'/>
+        </linearGradient>
+        <rect width='256' height='1' fill='url(#lg1)'/>
+    </svg>";
+
+    let srgb = render_str(svg, &resvg::RenderOptions::default());
+    let mut opt = resvg::RenderOptions::default();
+    opt.linear_output = true;
+    let linear = render_str(svg, &opt);
+
+    for (s, l) in srgb.pixels().iter().zip(linear.pixels()) {
+        assert_eq!(l.alpha(), 255);
+        assert!(to_linear(s.red()).abs_diff(l.red()) <= 1);
+        // 8-bit linear values are still distinct in sRGB.
+        assert_eq!(to_linear(to_srgb(l.red())), l.red());
+    }
+
+    // Black and white are preserved.
+    assert_eq!(linear.pixel(0, 0).unwrap().red(), 0);
+    assert_eq!(linear.pixel(255, 0).unwrap().red(), 255);
+}
+
+#[test]
+fn linear_output_with_nested_image() {
+    // A 1x1 SVG filled with #808080.
+    let svg = "<svg viewBox='0 0 2 1' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='1' height='1' fill='#808080'/>
+        <image x='1' width='1' height='1' href='data:image/svg+xml;base64,\
+            PHN2ZyB3aWR0aD0nMScgaGVpZ2h0PScxJyB4bWxucz0naHR0cDovL3d3dy53My5vcmcvMjAwMC9zdmcn\
+            PjxyZWN0IHdpZHRoPScxJyBoZWlnaHQ9JzEnIGZpbGw9JyM4MDgwODAnLz48L3N2Zz4='/>
+    </svg>";
+
+    let mut opt = resvg::RenderOptions::default();
+    opt.linear_output = true;
+    let pixmap = render_str(svg, &opt);
+
+    // The nested image must be converted only once.
+    let direct = pixmap.pixel(0, 0).unwrap();
+    assert_eq!(direct.red(), 55);
+    assert_eq!(pixmap.pixel(1, 0).unwrap(), direct);
+}
+
+#[test]
+fn geometry_bbox() {
+    let svg = "<svg width='400' height='400' viewBox='0 0 200 200'
+                    xmlns='http://www.w3.org/2000/svg'>
+        <rect x='20' y='30' width='40' height='50' fill='none' stroke='black' stroke-width='10'/>
+        <g transform='translate(100 100)'>
+            <circle cx='20' cy='20' r='10' filter='url(#filter1)'/>
+        </g>
+        <filter id='filter1'>
+            <feGaussianBlur stdDeviation='5'/>
+        </filter>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let bbox = rtree.geometry_bbox().unwrap();
+    assert_eq!(
+        (bbox.x(), bbox.y(), bbox.width(), bbox.height()),
+        (20.0, 30.0, 110.0, 100.0)
+    );
+
+    let svg = "<svg viewBox='0 0 200 200' xmlns='http://www.w3.org/2000/svg'/>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    assert!(resvg::Tree::from_usvg(&tree).geometry_bbox().is_none());
+}
+
+#[test]
+fn gaussian_blur_edge_mode() {
+    let svg = |edge_mode: &str, width: u32| {
+        format!(
+            "<svg viewBox='0 0 40 40' xmlns='http://www.w3.org/2000/svg'>
+                <filter id='filter1' filterUnits='userSpaceOnUse' x='0' y='0' width='40' height='40'>
+                    <feGaussianBlur stdDeviation='4' {}/>
+                </filter>
+                <rect width='{}' height='40' fill='green' filter='url(#filter1)'/>
+            </svg>",
+            edge_mode, width
+        )
+    };
+    let opt = resvg::RenderOptions::default();
+    let alpha = |pixmap: &tiny_skia::Pixmap, x| pixmap.pixel(x, 20).unwrap().alpha();
+
+    // Pixels outside the filter region are transparent black by default.
+    let pixmap = render_str(&svg("", 40), &opt);
+    assert_eq!(
+        pixmap.data(),
+        render_str(&svg("edgeMode='none'", 40), &opt).data()
+    );
+    assert!(alpha(&pixmap, 0) < 200);
+
+    let pixmap = render_str(&svg("edgeMode='duplicate'", 40), &opt);
+    assert!(pixmap.pixels().iter().all(|p| p.alpha() == 255));
+
+    let pixmap = render_str(&svg("edgeMode='duplicate'", 20), &opt);
+    assert_eq!(alpha(&pixmap, 39), 0);
+
+    // The left side is blurred into the right one.
+    let pixmap = render_str(&svg("edgeMode='wrap'", 20), &opt);
+    assert_ne!(alpha(&pixmap, 39), 0);
+    assert_eq!(alpha(&render_str(&svg("", 20), &opt), 39), 0);
+}
+
+#[test]
+fn gaussian_blur_matches_exact_gaussian() {
+    // The three box blurs approximation should stay close to a real Gaussian.
+    // The approximation error is the largest at small sigmas, about 3%.
+    for sigma in [2.0f64, 20.0] {
+        let svg = format!(
+            "<svg viewBox='0 0 200 20' xmlns='http://www.w3.org/2000/svg'>
+                <filter id='filter1' filterUnits='userSpaceOnUse' x='0' y='0' width='200' height='20'>
+                    <feGaussianBlur stdDeviation='{} 0'/>
+                </filter>
+                <rect x='80' width='40' height='20' filter='url(#filter1)'/>
+            </svg>",
+            sigma
+        );
+        let pixmap = render_str(&svg, &resvg::RenderOptions::default());
+
+        let radius = (sigma * 4.0).ceil() as i32;
+        let kernel: Vec<f64> = (-radius..=radius)
+            .map(|i| (-(i * i) as f64 / (2.0 * sigma * sigma)).exp())
+            .collect();
+        let sum: f64 = kernel.iter().sum();
+
+        for x in 0..200i32 {
+            let expected: f64 = (80..120)
+                .filter(|i| (x - i).abs() <= radius)
+                .map(|i| kernel[(x - i + radius) as usize])
+                .sum::<f64>()
+                / sum;
+            let alpha = pixmap.pixel(x as u32, 10).unwrap().alpha();
+            let diff = (expected * 255.0 - alpha as f64).abs();
+            assert!(
+                diff <= 8.0,
+                "sigma {} at x {}: {} vs {}",
+                sigma,
+                x,
+                alpha,
+                expected * 255.0
+            );
+        }
+    }
+}
+
+#[test]
+fn render_with_deadline() {
+    let svg = "<svg viewBox='0 0 10 10' xmlns='http://www.w3.org/2000/svg'>
+        <rect width='10' height='10' fill='green'/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+    let opt = resvg::RenderOptions::default();
+
+    let mut pixmap = tiny_skia::Pixmap::new(10, 10).unwrap();
+    let deadline = std::time::Instant::now();
+    let result = rtree.render_with_deadline(
+        &opt,
+        deadline,
+        tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+    assert_eq!(result, Err(resvg::RenderError::Timeout));
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 0);
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(60);
+    let result = rtree.render_with_deadline(
+        &opt,
+        deadline,
+        tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+    assert_eq!(result, Ok(()));
+    assert_eq!(pixmap.pixel(5, 5).unwrap().alpha(), 255);
+}
+
+#[test]
+fn render_with_deadline_and_nested_image() {
+    // A 20x10 SVG with a group and a rect, both 10x10.
+    let svg = "<svg viewBox='0 0 20 10' xmlns='http://www.w3.org/2000/svg'>
+        <image width='20' height='10' href='data:image/svg+xml;base64,\
+            PHN2ZyB3aWR0aD0nMjAnIGhlaWdodD0nMTAnIHhtbG5zPSdodHRwOi8vd3d3LnczLm9yZy8yMDAwL3N2\
+            Zyc+PGcgaWQ9J2cxJyBvcGFjaXR5PScwLjUnPjxyZWN0IHdpZHRoPScxMCcgaGVpZ2h0PScxMCcgZmls\
+            bD0nZ3JlZW4nLz48L2c+PHJlY3QgeD0nMTAnIHdpZHRoPScxMCcgaGVpZ2h0PScxMCcgZmlsbD0nZ3Jl\
+            ZW4nLz48L3N2Zz4='/>
+    </svg>";
+    let tree = usvg::Tree::from_str(svg, &usvg::Options::default()).unwrap();
+    let rtree = resvg::Tree::from_usvg(&tree);
+
+    let deadline = std::time::Instant::now() + std::time::Duration::from_millis(10);
+    let mut opt = resvg::RenderOptions::default();
+    // Lets the deadline pass in the middle of the nested image.
+    opt.on_group_rendered = Some(resvg::GroupRenderedCallback::new(move |id, _, _| {
+        if id == "g1" {
+            while std::time::Instant::now() <= deadline {
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+        }
+    }));
+
+    let mut pixmap = tiny_skia::Pixmap::new(20, 10).unwrap();
+    let result = rtree.render_with_deadline(
+        &opt,
+        deadline,
+        tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+    assert_eq!(result, Err(resvg::RenderError::Timeout));
+    // The second rect of the nested image is not rendered.
+    assert_eq!(pixmap.pixel(15, 5).unwrap().alpha(), 0);
+}
@@ -5,6 +5,7 @@ use usvg::{fontdb, TreeParsing, TreeTextToPath};
 #[rustfmt::skip]
 mod render;
 
+mod api;
 mod extra;
 
 const IMAGE_SIZE: u32 = 300;
@@ -22,7 +23,6 @@ static GLOBAL_FONTDB: Lazy<std::sync::Mutex<fontdb::Database>> = Lazy::new(|| {
 
 pub fn render(name: &str) -> usize {
     let svg_path = format!("tests/{}.svg", name);
-    let png_path = format!("tests/{}.png", name);
 
     let mut opt = usvg::Options::default();
     opt.resources_dir = Some(
@@ -53,35 +53,11 @@ pub fn render(name: &str) -> usize {
 
     // pixmap.save_png(&format!("tests/{}.png", name)).unwrap();
 
-    let mut rgba = pixmap.take();
-    svgfilters::demultiply_alpha(rgba.as_mut_slice().as_rgba_mut());
-
-    let expected_data = load_png(&png_path);
-    assert_eq!(expected_data.len(), rgba.len());
-
-    let mut pixels_d = 0;
-    for (a, b) in expected_data
-        .as_slice()
-        .as_rgba()
-        .iter()
-        .zip(rgba.as_rgba())
-    {
-        if is_pix_diff(*a, *b) {
-            pixels_d += 1;
-        }
-    }
-
-    // Save diff if needed.
-    // if pixels_d != 0 {
-    //     gen_diff(&name, &expected_data, rgba.as_slice()).unwrap();
-    // }
-
-    pixels_d
+    compare_with_reference(name, &pixmap)
 }
 
 pub fn render_extra_with_scale(name: &str, scale: f32) -> usize {
     let svg_path = format!("tests/{}.svg", name);
-    let png_path = format!("tests/{}.png", name);
 
     let opt = usvg::Options::default();
 
@@ -101,36 +77,33 @@ pub fn render_extra_with_scale(name: &str, scale: f32) -> usize {
 
     // pixmap.save_png(&format!("tests/{}.png", name)).unwrap();
 
-    let mut rgba = pixmap.take();
-    svgfilters::demultiply_alpha(rgba.as_mut_slice().as_rgba_mut());
-
-    let expected_data = load_png(&png_path);
-    assert_eq!(expected_data.len(), rgba.len());
-
-    let mut pixels_d = 0;
-    for (a, b) in expected_data
-        .as_slice()
-        .as_rgba()
-        .iter()
-        .zip(rgba.as_rgba())
-    {
-        if is_pix_diff(*a, *b) {
-            pixels_d += 1;
-        }
-    }
-
-    // Save diff if needed.
-    // if pixels_d != 0 {
-    //     gen_diff(&name, &expected_data, rgba.as_slice()).unwrap();
-    // }
-
-    pixels_d
+    compare_with_reference(name, &pixmap)
 }
 
 pub fn render_extra(name: &str) -> usize {
     render_extra_with_scale(name, 1.0)
 }
 
+fn compare_with_reference(name: &str, pixmap: &tiny_skia::Pixmap) -> usize {
+    // Reference images are stored demultiplied, therefore compare them as is.
+    let mut expected = tiny_skia::Pixmap::new(pixmap.width(), pixmap.height()).unwrap();
+    let expected_data = load_png(&format!("tests/{}.png", name));
+    assert_eq!(expected_data.len(), expected.data().len());
+    expected.data_mut().copy_from_slice(&expected_data);
+
+    let mut actual = pixmap.clone();
+    svgfilters::demultiply_alpha(actual.data_mut().as_rgba_mut());
+
+    let diff = resvg::testing::compare_with_tolerance(&expected, &actual, 1).unwrap();
+
+    // Save diff if needed.
+    // if diff.differing_pixels != 0 {
+    //     gen_diff(name, &expected, &actual).unwrap();
+    // }
+
+    diff.differing_pixels
+}
+
 fn load_png(path: &str) -> Vec<u8> {
     let data = std::fs::read(path).unwrap();
     let mut decoder = png::Decoder::new(data.as_slice());
@@ -174,28 +147,24 @@ fn load_png(path: &str) -> Vec<u8> {
     }
 }
 
-// TODO: remove
-fn is_pix_diff(c1: rgb::RGBA8, c2: rgb::RGBA8) -> bool {
-    (c1.r as i32 - c2.r as i32).abs() > 1
-        || (c1.g as i32 - c2.g as i32).abs() > 1
-        || (c1.b as i32 - c2.b as i32).abs() > 1
-        || (c1.a as i32 - c2.a as i32).abs() > 1
-}
-
 #[allow(dead_code)]
-fn gen_diff(name: &str, img1: &[u8], img2: &[u8]) -> Result<(), png::EncodingError> {
-    assert_eq!(img1.len(), img2.len());
-
-    let mut img3 = Vec::with_capacity((img1.len() as f32 * 0.75).round() as usize);
-    for (a, b) in img1.as_rgba().iter().zip(img2.as_rgba()) {
-        if is_pix_diff(*a, *b) {
-            img3.push(255);
-            img3.push(0);
-            img3.push(0);
+fn gen_diff(
+    name: &str,
+    img1: &tiny_skia::Pixmap,
+    img2: &tiny_skia::Pixmap,
+) -> Result<(), png::EncodingError> {
+    assert_eq!(img1.data().len(), img2.data().len());
+
+    let mut img3 = Vec::with_capacity(img1.data().len() / 4 * 3);
+    for (a, b) in img1.pixels().iter().zip(img2.pixels()) {
+        let is_diff = a.red().abs_diff(b.red()) > 1
+            || a.green().abs_diff(b.green()) > 1
+            || a.blue().abs_diff(b.blue()) > 1
+            || a.alpha().abs_diff(b.alpha()) > 1;
+        if is_diff {
+            img3.extend_from_slice(&[255, 0, 0]);
         } else {
-            img3.push(255);
-            img3.push(255);
-            img3.push(255);
+            img3.extend_from_slice(&[255, 255, 255]);
         }
     }
 
@@ -203,7 +172,7 @@ fn gen_diff(name: &str, img1: &[u8], img2: &[u8]) -> Result<(), png::EncodingErr
     let file = std::fs::File::create(path)?;
     let ref mut w = std::io::BufWriter::new(file);
 
-    let mut encoder = png::Encoder::new(w, IMAGE_SIZE, IMAGE_SIZE);
+    let mut encoder = png::Encoder::new(w, img1.width(), img1.height());
     encoder.set_color(png::ColorType::Rgb);
     encoder.set_depth(png::BitDepth::Eight);
     let mut writer = encoder.write_header()?;
@@ -55,6 +55,8 @@ use crate::render;
 #[test] fn filters_feComponentTransfer_mixed_types() { assert_eq!(render("tests/filters/feComponentTransfer/mixed-types"), 0); }
 #[test] fn filters_feComponentTransfer_no_children() { assert_eq!(render("tests/filters/feComponentTransfer/no-children"), 0); }
 #[test] fn filters_feComponentTransfer_type_eq_discrete_on_blue() { assert_eq!(render("tests/filters/feComponentTransfer/type=discrete-on-blue"), 0); }
+#[test] fn filters_feComponentTransfer_type_eq_discrete_posterize() { assert_eq!(render("tests/filters/feComponentTransfer/type=discrete-posterize"), 0); }
+#[test] fn filters_feComponentTransfer_type_eq_gamma_curve() { assert_eq!(render("tests/filters/feComponentTransfer/type=gamma-curve"), 0); }
 #[test] fn filters_feComponentTransfer_type_eq_gamma_on_blue() { assert_eq!(render("tests/filters/feComponentTransfer/type=gamma-on-blue"), 0); }
 #[test] fn filters_feComponentTransfer_type_eq_gamma_with_an_invalid_offset() { assert_eq!(render("tests/filters/feComponentTransfer/type=gamma-with-an-invalid-offset"), 0); }
 #[test] fn filters_feComponentTransfer_type_eq_gamma_with_invalid_values() { assert_eq!(render("tests/filters/feComponentTransfer/type=gamma-with-invalid-values"), 0); }
@@ -96,6 +98,7 @@ use crate::render;
 #[test] fn filters_feConvolveMatrix_bias_eq_9999() { assert_eq!(render("tests/filters/feConvolveMatrix/bias=9999"), 0); }
 #[test] fn filters_feConvolveMatrix_custom_divisor() { assert_eq!(render("tests/filters/feConvolveMatrix/custom-divisor"), 0); }
 #[test] fn filters_feConvolveMatrix_divisor_eq_0() { assert_eq!(render("tests/filters/feConvolveMatrix/divisor=0"), 0); }
+#[test] fn filters_feConvolveMatrix_edge_detect() { assert_eq!(render("tests/filters/feConvolveMatrix/edge-detect"), 0); }
 #[test] fn filters_feConvolveMatrix_edgeMode_eq_none() { assert_eq!(render("tests/filters/feConvolveMatrix/edgeMode=none"), 0); }
 #[test] fn filters_feConvolveMatrix_edgeMode_eq_wrap_with_matrix_larger_than_target() { assert_eq!(render("tests/filters/feConvolveMatrix/edgeMode=wrap-with-matrix-larger-than-target"), 0); }
 #[test] fn filters_feConvolveMatrix_edgeMode_eq_wrap() { assert_eq!(render("tests/filters/feConvolveMatrix/edgeMode=wrap"), 0); }
@@ -111,6 +114,7 @@ use crate::render;
 #[test] fn filters_feConvolveMatrix_order_eq_4_4() { assert_eq!(render("tests/filters/feConvolveMatrix/order=4-4"), 0); }
 #[test] fn filters_feConvolveMatrix_order_eq_4() { assert_eq!(render("tests/filters/feConvolveMatrix/order=4"), 0); }
 #[test] fn filters_feConvolveMatrix_preserveAlpha_eq_true() { assert_eq!(render("tests/filters/feConvolveMatrix/preserveAlpha=true"), 0); }
+#[test] fn filters_feConvolveMatrix_sharpen() { assert_eq!(render("tests/filters/feConvolveMatrix/sharpen"), 0); }
 #[test] fn filters_feConvolveMatrix_targetX_eq__1() { assert_eq!(render("tests/filters/feConvolveMatrix/targetX=-1"), 0); }
 #[test] fn filters_feConvolveMatrix_targetX_eq_0() { assert_eq!(render("tests/filters/feConvolveMatrix/targetX=0"), 0); }
 #[test] fn filters_feConvolveMatrix_targetX_eq_2() { assert_eq!(render("tests/filters/feConvolveMatrix/targetX=2"), 0); }
@@ -138,6 +142,7 @@ use crate::render;
 #[test] fn filters_feDiffuseLighting_surfaceScale_eq_0() { assert_eq!(render("tests/filters/feDiffuseLighting/surfaceScale=0"), 0); }
 #[test] fn filters_feDiffuseLighting_surfaceScale_eq_1_33() { assert_eq!(render("tests/filters/feDiffuseLighting/surfaceScale=1.33"), 0); }
 #[test] fn filters_feDiffuseLighting_surfaceScale_eq_5() { assert_eq!(render("tests/filters/feDiffuseLighting/surfaceScale=5"), 0); }
+#[test] fn filters_feDisplacementMap_checkerboard_with_gradient_map() { assert_eq!(render("tests/filters/feDisplacementMap/checkerboard-with-gradient-map"), 0); }
 #[test] fn filters_feDisplacementMap_simple_case() { assert_eq!(render("tests/filters/feDisplacementMap/simple-case"), 0); }
 #[test] fn filters_feDistantLight_default_attributes() { assert_eq!(render("tests/filters/feDistantLight/default-attributes"), 0); }
 #[test] fn filters_feDistantLight_negative_azimuth_and_elevation() { assert_eq!(render("tests/filters/feDistantLight/negative-azimuth-and-elevation"), 0); }
@@ -164,12 +169,15 @@ use crate::render;
 #[test] fn filters_feGaussianBlur_huge_stdDeviation() { assert_eq!(render("tests/filters/feGaussianBlur/huge-stdDeviation"), 0); }
 #[test] fn filters_feGaussianBlur_negative_stdDeviation() { assert_eq!(render("tests/filters/feGaussianBlur/negative-stdDeviation"), 0); }
 #[test] fn filters_feGaussianBlur_no_stdDeviation() { assert_eq!(render("tests/filters/feGaussianBlur/no-stdDeviation"), 0); }
+#[test] fn filters_feGaussianBlur_on_SourceAlpha() { assert_eq!(render("tests/filters/feGaussianBlur/on-SourceAlpha"), 0); }
 #[test] fn filters_feGaussianBlur_simple_case() { assert_eq!(render("tests/filters/feGaussianBlur/simple-case"), 0); }
 #[test] fn filters_feGaussianBlur_small_stdDeviation() { assert_eq!(render("tests/filters/feGaussianBlur/small-stdDeviation"), 0); }
 #[test] fn filters_feGaussianBlur_stdDeviation_with_multiple_values() { assert_eq!(render("tests/filters/feGaussianBlur/stdDeviation-with-multiple-values"), 0); }
 #[test] fn filters_feGaussianBlur_stdDeviation_with_two_different_values() { assert_eq!(render("tests/filters/feGaussianBlur/stdDeviation-with-two-different-values"), 0); }
 #[test] fn filters_feGaussianBlur_stdDeviation_with_two_values() { assert_eq!(render("tests/filters/feGaussianBlur/stdDeviation-with-two-values"), 0); }
 #[test] fn filters_feGaussianBlur_stdDeviation_eq_0_5() { assert_eq!(render("tests/filters/feGaussianBlur/stdDeviation=0-5"), 0); }
+#[test] fn filters_feGaussianBlur_stdDeviation_eq_2() { assert_eq!(render("tests/filters/feGaussianBlur/stdDeviation=2"), 0); }
+#[test] fn filters_feGaussianBlur_stdDeviation_eq_20() { assert_eq!(render("tests/filters/feGaussianBlur/stdDeviation=20"), 0); }
 #[test] fn filters_feGaussianBlur_stdDeviation_eq_5_0() { assert_eq!(render("tests/filters/feGaussianBlur/stdDeviation=5-0"), 0); }
 #[test] fn filters_feGaussianBlur_tiny_stdDeviation() { assert_eq!(render("tests/filters/feGaussianBlur/tiny-stdDeviation"), 0); }
 #[test] fn filters_feImage_chained_feImage() { assert_eq!(render("tests/filters/feImage/chained-feImage"), 0); }
@@ -213,6 +221,7 @@ use crate::render;
 #[test] fn filters_feMorphology_source_with_opacity() { assert_eq!(render("tests/filters/feMorphology/source-with-opacity"), 0); }
 #[test] fn filters_feMorphology_zero_radius() { assert_eq!(render("tests/filters/feMorphology/zero-radius"), 0); }
 #[test] fn filters_feOffset_complex_transform() { assert_eq!(render("tests/filters/feOffset/complex-transform"), 0); }
+#[test] fn filters_feOffset_dx_eq_1_5_dy_eq_0_5() { assert_eq!(render("tests/filters/feOffset/dx=1.5-dy=0.5"), 0); }
 #[test] fn filters_feOffset_fractional_offset() { assert_eq!(render("tests/filters/feOffset/fractional-offset"), 0); }
 #[test] fn filters_feOffset_negative_offset() { assert_eq!(render("tests/filters/feOffset/negative-offset"), 0); }
 #[test] fn filters_feOffset_no_offset() { assert_eq!(render("tests/filters/feOffset/no-offset"), 0); }
@@ -248,6 +257,7 @@ use crate::render;
 #[test] fn filters_feTile_complex_transform() { assert_eq!(render("tests/filters/feTile/complex-transform"), 0); }
 #[test] fn filters_feTile_empty_region() { assert_eq!(render("tests/filters/feTile/empty-region"), 0); }
 #[test] fn filters_feTile_simple_case() { assert_eq!(render("tests/filters/feTile/simple-case"), 0); }
+#[test] fn filters_feTile_with_feImage() { assert_eq!(render("tests/filters/feTile/with-feImage"), 0); }
 #[test] fn filters_feTile_with_region() { assert_eq!(render("tests/filters/feTile/with-region"), 0); }
 #[test] fn filters_feTile_with_subregion_1() { assert_eq!(render("tests/filters/feTile/with-subregion-1"), 0); }
 #[test] fn filters_feTile_with_subregion_2() { assert_eq!(render("tests/filters/feTile/with-subregion-2"), 0); }
@@ -354,6 +364,7 @@ use crate::render;
 #[test] fn filters_filter_functions_color_adjust_functions_200percent() { assert_eq!(render("tests/filters/filter-functions/color-adjust-functions-200percent"), 0); }
 #[test] fn filters_filter_functions_color_adjust_functions_50percent() { assert_eq!(render("tests/filters/filter-functions/color-adjust-functions-50percent"), 0); }
 #[test] fn filters_filter_functions_color_adjust_functions_default_value() { assert_eq!(render("tests/filters/filter-functions/color-adjust-functions-default-value"), 0); }
+#[test] fn filters_filter_functions_color_adjust_functions_in_style_attribute() { assert_eq!(render("tests/filters/filter-functions/color-adjust-functions-in-style-attribute"), 0); }
 #[test] fn filters_filter_functions_color_adjust_functions_negative() { assert_eq!(render("tests/filters/filter-functions/color-adjust-functions-negative"), 0); }
 #[test] fn filters_filter_functions_drop_shadow_function_color_as_attribute() { assert_eq!(render("tests/filters/filter-functions/drop-shadow-function-color-as-attribute"), 0); }
 #[test] fn filters_filter_functions_drop_shadow_function_color_last() { assert_eq!(render("tests/filters/filter-functions/drop-shadow-function-color-last"), 0); }
@@ -378,6 +389,7 @@ use crate::render;
 #[test] fn filters_filter_functions_hue_rotate_function_999deg() { assert_eq!(render("tests/filters/filter-functions/hue-rotate-function-999deg"), 0); }
 #[test] fn filters_filter_functions_hue_rotate_function_default_value() { assert_eq!(render("tests/filters/filter-functions/hue-rotate-function-default-value"), 0); }
 #[test] fn filters_filter_functions_hue_rotate_function_zero() { assert_eq!(render("tests/filters/filter-functions/hue-rotate-function-zero"), 0); }
+#[test] fn filters_filter_functions_in_style_attribute() { assert_eq!(render("tests/filters/filter-functions/in-style-attribute"), 0); }
 #[test] fn filters_filter_functions_nested_filters() { assert_eq!(render("tests/filters/filter-functions/nested-filters"), 0); }
 #[test] fn filters_filter_functions_one_invalid_function_in_list() { assert_eq!(render("tests/filters/filter-functions/one-invalid-function-in-list"), 0); }
 #[test] fn filters_filter_functions_one_invalid_url_in_list() { assert_eq!(render("tests/filters/filter-functions/one-invalid-url-in-list"), 0); }
@@ -429,6 +441,7 @@ use crate::render;
 #[test] fn masking_clipPath_no_children() { assert_eq!(render("tests/masking/clipPath/no-children"), 0); }
 #[test] fn masking_clipPath_none() { assert_eq!(render("tests/masking/clipPath/none"), 0); }
 #[test] fn masking_clipPath_on_a_horizontal_line() { assert_eq!(render("tests/masking/clipPath/on-a-horizontal-line"), 0); }
+#[test] fn masking_clipPath_on_nested_groups() { assert_eq!(render("tests/masking/clipPath/on-nested-groups"), 0); }
 #[test] fn masking_clipPath_on_the_root_svg_with_size() { assert_eq!(render("tests/masking/clipPath/on-the-root-svg-with-size"), 0); }
 #[test] fn masking_clipPath_on_the_root_svg_without_size() { assert_eq!(render("tests/masking/clipPath/on-the-root-svg-without-size"), 0); }
 #[test] fn masking_clipPath_opacity_has_no_effect() { assert_eq!(render("tests/masking/clipPath/opacity-has-no-effect"), 0); }
@@ -632,6 +645,7 @@ use crate::render;
 #[test] fn paint_servers_stop_color_simple_case() { assert_eq!(render("tests/paint-servers/stop-color/simple-case"), 0); }
 #[test] fn paint_servers_stop_opacity_50percent() { assert_eq!(render("tests/paint-servers/stop-opacity/50percent"), 0); }
 #[test] fn paint_servers_stop_opacity_simple_case() { assert_eq!(render("tests/paint-servers/stop-opacity/simple-case"), 0); }
+#[test] fn paint_servers_stop_opacity_with_skewed_gradientTransform_on_layer() { assert_eq!(render("tests/paint-servers/stop-opacity/with-skewed-gradientTransform-on-layer"), 0); }
 #[test] fn painting_color_inherit() { assert_eq!(render("tests/painting/color/inherit"), 0); }
 #[test] fn painting_color_simple_case() { assert_eq!(render("tests/painting/color/simple-case"), 0); }
 #[test] fn painting_display_bBox_impact() { assert_eq!(render("tests/painting/display/bBox-impact"), 0); }
@@ -758,6 +772,8 @@ use crate::render;
 #[test] fn painting_marker_orient_eq_auto_on_M_L_M_C() { assert_eq!(render("tests/painting/marker/orient=auto-on-M-L-M-C"), 0); }
 #[test] fn painting_marker_orient_eq_auto_on_M_L_Z() { assert_eq!(render("tests/painting/marker/orient=auto-on-M-L-Z"), 0); }
 #[test] fn painting_marker_orient_eq_auto_on_M_L() { assert_eq!(render("tests/painting/marker/orient=auto-on-M-L"), 0); }
+#[test] fn painting_marker_orient_eq_auto_on_sharp_turns() { assert_eq!(render("tests/painting/marker/orient=auto-on-sharp-turns"), 0); }
+#[test] fn painting_marker_orient_eq_auto_start_reverse() { assert_eq!(render("tests/painting/marker/orient=auto-start-reverse"), 0); }
 #[test] fn painting_marker_percent_values() { assert_eq!(render("tests/painting/marker/percent-values"), 0); }
 #[test] fn painting_marker_recursive_1() { assert_eq!(render("tests/painting/marker/recursive-1"), 0); }
 #[test] fn painting_marker_recursive_2() { assert_eq!(render("tests/painting/marker/recursive-2"), 0); }
@@ -773,6 +789,7 @@ use crate::render;
 #[test] fn painting_marker_with_an_image_child() { assert_eq!(render("tests/painting/marker/with-an-image-child"), 0); }
 #[test] fn painting_marker_with_invalid_markerUnits() { assert_eq!(render("tests/painting/marker/with-invalid-markerUnits"), 0); }
 #[test] fn painting_marker_with_markerUnits_eq_userSpaceOnUse() { assert_eq!(render("tests/painting/marker/with-markerUnits=userSpaceOnUse"), 0); }
+#[test] fn painting_marker_with_overflow_eq_visible() { assert_eq!(render("tests/painting/marker/with-overflow=visible"), 0); }
 #[test] fn painting_marker_with_viewBox_1() { assert_eq!(render("tests/painting/marker/with-viewBox-1"), 0); }
 #[test] fn painting_marker_with_viewBox_2() { assert_eq!(render("tests/painting/marker/with-viewBox-2"), 0); }
 #[test] fn painting_marker_zero_length_path_1() { assert_eq!(render("tests/painting/marker/zero-length-path-1"), 0); }
@@ -865,6 +882,8 @@ use crate::render;
 #[test] fn painting_stroke_dasharray_none() { assert_eq!(render("tests/painting/stroke-dasharray/none"), 0); }
 #[test] fn painting_stroke_dasharray_odd_list() { assert_eq!(render("tests/painting/stroke-dasharray/odd-list"), 0); }
 #[test] fn painting_stroke_dasharray_on_a_circle() { assert_eq!(render("tests/painting/stroke-dasharray/on-a-circle"), 0); }
+#[test] fn painting_stroke_dasharray_with_linecap() { assert_eq!(render("tests/painting/stroke-dasharray/with-linecap"), 0); }
+#[test] fn painting_stroke_dasharray_with_pathLength() { assert_eq!(render("tests/painting/stroke-dasharray/with-pathLength"), 0); }
 #[test] fn painting_stroke_dasharray_ws_separator() { assert_eq!(render("tests/painting/stroke-dasharray/ws-separator"), 0); }
 #[test] fn painting_stroke_dasharray_zero_sum() { assert_eq!(render("tests/painting/stroke-dasharray/zero-sum"), 0); }
 #[test] fn painting_stroke_dashoffset_default() { assert_eq!(render("tests/painting/stroke-dashoffset/default"), 0); }
@@ -1063,10 +1082,12 @@ use crate::render;
 #[test] fn structure_image_embedded_jpeg_as_image_jpg() { assert_eq!(render("tests/structure/image/embedded-jpeg-as-image-jpg"), 0); }
 #[test] fn structure_image_embedded_jpeg_without_mime() { assert_eq!(render("tests/structure/image/embedded-jpeg-without-mime"), 0); }
 #[test] fn structure_image_embedded_png() { assert_eq!(render("tests/structure/image/embedded-png"), 0); }
+#[test] fn structure_image_embedded_svg_with_embedded_svg() { assert_eq!(render("tests/structure/image/embedded-svg-with-embedded-svg"), 0); }
 #[test] fn structure_image_embedded_svg_without_mime() { assert_eq!(render("tests/structure/image/embedded-svg-without-mime"), 0); }
 #[test] fn structure_image_embedded_svg() { assert_eq!(render("tests/structure/image/embedded-svg"), 0); }
 #[test] fn structure_image_embedded_svgz() { assert_eq!(render("tests/structure/image/embedded-svgz"), 0); }
 #[test] fn structure_image_external_gif() { assert_eq!(render("tests/structure/image/external-gif"), 0); }
+#[test] fn structure_image_external_jpeg_with_exif_orientation() { assert_eq!(render("tests/structure/image/external-jpeg-with-exif-orientation"), 0); }
 #[test] fn structure_image_external_jpeg() { assert_eq!(render("tests/structure/image/external-jpeg"), 0); }
 #[test] fn structure_image_external_png() { assert_eq!(render("tests/structure/image/external-png"), 0); }
 #[test] fn structure_image_external_svg_with_transform() { assert_eq!(render("tests/structure/image/external-svg-with-transform"), 0); }
@@ -1219,6 +1240,12 @@ use crate::render;
 #[test] fn structure_transform_translate_without_Y() { assert_eq!(render("tests/structure/transform/translate-without-Y"), 0); }
 #[test] fn structure_transform_translate() { assert_eq!(render("tests/structure/transform/translate"), 0); }
 #[test] fn structure_transform_zeroed_matrix() { assert_eq!(render("tests/structure/transform/zeroed-matrix"), 0); }
+#[test] fn structure_transform_origin_center() { assert_eq!(render("tests/structure/transform-origin/center"), 0); }
+#[test] fn structure_transform_origin_in_style_attribute() { assert_eq!(render("tests/structure/transform-origin/in-style-attribute"), 0); }
+#[test] fn structure_transform_origin_keywords_in_reverse_order() { assert_eq!(render("tests/structure/transform-origin/keywords-in-reverse-order"), 0); }
+#[test] fn structure_transform_origin_length() { assert_eq!(render("tests/structure/transform-origin/length"), 0); }
+#[test] fn structure_transform_origin_on_use() { assert_eq!(render("tests/structure/transform-origin/on-use"), 0); }
+#[test] fn structure_transform_origin_without_transform() { assert_eq!(render("tests/structure/transform-origin/without-transform"), 0); }
 #[test] fn structure_use_cSS_rules() { assert_eq!(render("tests/structure/use/cSS-rules"), 0); }
 #[test] fn structure_use_complex_style_resolving_order() { assert_eq!(render("tests/structure/use/complex-style-resolving-order"), 0); }
 #[test] fn structure_use_display_inheritance() { assert_eq!(render("tests/structure/use/display-inheritance"), 0); }
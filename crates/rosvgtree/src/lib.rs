@@ -713,6 +713,7 @@ impl AttributeId {
                 | AttributeId::TextOverflow
                 | AttributeId::TextRendering
                 | AttributeId::Transform
+                | AttributeId::TransformOrigin
                 | AttributeId::UnicodeBidi
                 | AttributeId::VectorEffect
                 | AttributeId::Visibility
@@ -739,6 +740,7 @@ impl AttributeId {
                 | AttributeId::ClipPath
                 | AttributeId::ClipRule
                 | AttributeId::Color
+                | AttributeId::ColorInterpolation
                 | AttributeId::ColorInterpolationFilters
                 | AttributeId::Direction
                 | AttributeId::Display
@@ -805,5 +807,6 @@ fn is_non_inheritable(id: AttributeId) -> bool {
             | AttributeId::StopOpacity
             | AttributeId::TextDecoration
             | AttributeId::Transform
+            | AttributeId::TransformOrigin
     )
 }
@@ -269,15 +269,15 @@ pub(crate) fn parse_svg_element<'input>(
 
     // Split a `style` attribute.
     if let Some(value) = xml_node.attribute("style") {
-        for declaration in simplecss::DeclarationTokenizer::from(value) {
+        split_style(value, |name, value| {
             // TODO: preform XML attribute normalization
-            if let Some(aid) = AttributeId::from_str(declaration.name) {
+            if let Some(aid) = AttributeId::from_str(name) {
                 // Parse only the presentation attributes.
                 if aid.is_presentation() {
-                    insert_attribute(aid, declaration.value);
+                    insert_attribute(aid, value);
                 }
             }
-        }
+        });
     }
 
     if doc.nodes.len() > 1_000_000 {
@@ -295,6 +295,57 @@ pub(crate) fn parse_svg_element<'input>(
     Ok(node_id)
 }
 
+/// Splits a `style` attribute into declarations.
+///
+/// `simplecss` stops parsing a function at the first closing parenthesis,
+/// which truncates values with nested functions, like
+/// `drop-shadow(1px 1px rgba(0, 0, 0, 0.5))`, and skips all the following declarations.
+/// Such values are restored from the source text and parsing continues after them.
+fn split_style<'a>(mut text: &'a str, mut f: impl FnMut(&'a str, &'a str)) {
+    loop {
+        let mut restart = None;
+        for declaration in simplecss::DeclarationTokenizer::from(text) {
+            let start = declaration.value.as_ptr() as usize - text.as_ptr() as usize;
+            let end = restore_nested_functions(text, start, declaration.value.len());
+            f(declaration.name, text[start..end].trim());
+
+            if end != start + declaration.value.len() {
+                restart = Some(end);
+                break;
+            }
+        }
+
+        match restart {
+            Some(end) => {
+                text = text[end..].trim_start_matches(|c: char| c == ';' || c.is_whitespace())
+            }
+            None => break,
+        }
+    }
+}
+
+/// Returns the end of a declaration value, which ends at the first `;`
+/// outside of parentheses.
+fn restore_nested_functions(text: &str, start: usize, len: usize) -> usize {
+    let value = &text[start..start + len];
+    let mut depth = value.matches('(').count() as i32 - value.matches(')').count() as i32;
+    if depth <= 0 {
+        return start + len;
+    }
+
+    let from = start + len;
+    for (i, c) in text[from..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ';' if depth <= 0 => return from + i,
+            _ => {}
+        }
+    }
+
+    text.len()
+}
+
 fn append_attribute<'input>(
     parent_id: NodeId,
     tag_name: ElementId,
@@ -302,12 +353,9 @@ fn append_attribute<'input>(
     value: roxmltree::StringStorage<'input>,
     doc: &mut Document<'input>,
 ) -> bool {
-    match aid {
-        // The `style` attribute will be split into attributes, so we don't need it.
-        AttributeId::Style |
-        // No need to copy a `class` attribute since CSS were already resolved.
-        AttributeId::Class => return false,
-        _ => {}
+    // The `style` attribute will be split into attributes, so we don't need it.
+    if aid == AttributeId::Style {
+        return false;
     }
 
     // Ignore `xlink:href` on `tspan` (which was originally `tref` or `a`),
@@ -92,21 +92,49 @@ pub fn component_transfer(
     func_a: TransferFunction,
     src: ImageRefMut,
 ) {
+    let lut_b = LookupTable::new(&func_b);
+    let lut_g = LookupTable::new(&func_g);
+    let lut_r = LookupTable::new(&func_r);
+    let lut_a = LookupTable::new(&func_a);
+
     for pixel in src.data {
-        if !func_b.is_dummy() {
-            pixel.b = func_b.apply(pixel.b);
+        if let Some(ref lut) = lut_b {
+            pixel.b = lut.apply(pixel.b);
+        }
+
+        if let Some(ref lut) = lut_g {
+            pixel.g = lut.apply(pixel.g);
         }
 
-        if !func_g.is_dummy() {
-            pixel.g = func_g.apply(pixel.g);
+        if let Some(ref lut) = lut_r {
+            pixel.r = lut.apply(pixel.r);
         }
 
-        if !func_r.is_dummy() {
-            pixel.r = func_r.apply(pixel.r);
+        if let Some(ref lut) = lut_a {
+            pixel.a = lut.apply(pixel.a);
+        }
+    }
+}
+
+/// A precomputed transfer function result for each possible component value.
+struct LookupTable([u8; 256]);
+
+impl LookupTable {
+    fn new(func: &TransferFunction) -> Option<Self> {
+        if func.is_dummy() {
+            return None;
         }
 
-        if !func_a.is_dummy() {
-            pixel.a = func_a.apply(pixel.a);
+        let mut table = [0; 256];
+        for (i, v) in table.iter_mut().enumerate() {
+            *v = func.apply(i as u8);
         }
+
+        Some(LookupTable(table))
+    }
+
+    #[inline]
+    fn apply(&self, c: u8) -> u8 {
+        self.0[c as usize]
     }
 }
@@ -6,7 +6,7 @@ use alloc::vec;
 
 use crate::{f64_bound, FuzzyZero, ImageRefMut, RGBA8};
 
-/// An edges processing mode used by `convolve_matrix`.
+/// An edges processing mode used by `convolve_matrix` and `box_blur`.
 #[allow(missing_docs)]
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum EdgeMode {
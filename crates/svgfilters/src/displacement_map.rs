@@ -2,7 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use crate::{ImageRef, ImageRefMut};
+use crate::{ImageRef, ImageRefMut, RGBA8};
 
 /// A color channel.
 #[allow(missing_docs)]
@@ -17,7 +17,10 @@ pub enum ColorChannel {
 /// Applies a displacement map.
 ///
 /// - `map` pixels should have a **unpremultiplied alpha**.
-/// - `src` pixels can have any alpha method.
+/// - `src` pixels should have a **premultiplied alpha**, since they are interpolated.
+///
+/// Displaced pixels are sampled using a bilinear interpolation.
+/// Pixels outside of `src` are treated as transparent black.
 ///
 /// `sx` and `sy` indicate canvas scale.
 ///
@@ -39,9 +42,18 @@ pub fn displacement_map(
     let w = src.width as i32;
     let h = src.height as i32;
 
+    // Pixels outside the image are transparent black.
+    let get = |x: i32, y: i32| {
+        if x >= 0 && x < w && y >= 0 && y < h {
+            src.data[(y * w + x) as usize]
+        } else {
+            RGBA8::default()
+        }
+    };
+
     let mut x: u32 = 0;
     let mut y: u32 = 0;
-    for pixel in map.data.iter() {
+    for (pixel, dest_pixel) in map.data.iter().zip(dest.data.iter_mut()) {
         let calc_offset = |channel| {
             let c = match channel {
                 ColorChannel::B => pixel.b,
@@ -55,17 +67,38 @@ pub fn displacement_map(
 
         let dx = calc_offset(x_channel_selector);
         let dy = calc_offset(y_channel_selector);
-        let ox = (x as f64 + dx * sx).round() as i32;
-        let oy = (y as f64 + dy * sy).round() as i32;
+        let ox = x as f64 + dx * sx;
+        let oy = y as f64 + dy * sy;
 
-        // TODO: we should use some kind of anti-aliasing when offset is on a pixel border
+        // Bilinear interpolation between four neighbour pixels.
+        let x0 = ox.floor();
+        let y0 = oy.floor();
+        let tx = ox - x0;
+        let ty = oy - y0;
+        let (x0, y0) = (x0 as i32, y0 as i32);
 
-        if x < w as u32 && y < h as u32 && ox >= 0 && ox < w && oy >= 0 && oy < h {
-            let idx = (oy * w + ox) as usize;
-            let idx1 = (y * w as u32 + x) as usize;
-            dest.data[idx1] = src.data[idx];
+        let samples = [
+            (get(x0, y0), (1.0 - tx) * (1.0 - ty)),
+            (get(x0 + 1, y0), tx * (1.0 - ty)),
+            (get(x0, y0 + 1), (1.0 - tx) * ty),
+            (get(x0 + 1, y0 + 1), tx * ty),
+        ];
+
+        let mut c = [0.0; 4];
+        for (p, k) in samples.iter() {
+            c[0] += p.r as f64 * k;
+            c[1] += p.g as f64 * k;
+            c[2] += p.b as f64 * k;
+            c[3] += p.a as f64 * k;
         }
 
+        *dest_pixel = RGBA8 {
+            r: (c[0] + 0.5) as u8,
+            g: (c[1] + 0.5) as u8,
+            b: (c[2] + 0.5) as u8,
+            a: (c[3] + 0.5) as u8,
+        };
+
         x += 1;
         if x == src.width {
             x = 0;
@@ -28,9 +28,8 @@
 
 use alloc::vec;
 use alloc::vec::Vec;
-use rgb::ComponentSlice;
 
-use crate::ImageRefMut;
+use crate::{ImageRefMut, RGBA8};
 
 struct BlurData {
     width: usize,
@@ -62,22 +61,26 @@ pub fn iir_blur(sigma_x: f64, sigma_y: f64, src: ImageRefMut) {
         steps: 4,
     };
 
-    let data = src.data.as_mut_slice();
-    gaussian_channel(data, &d, 0, buf);
-    gaussian_channel(data, &d, 1, buf);
-    gaussian_channel(data, &d, 2, buf);
-    gaussian_channel(data, &d, 3, buf);
+    gaussian_channel(src.data, &d, |p| &mut p.r, buf);
+    gaussian_channel(src.data, &d, |p| &mut p.g, buf);
+    gaussian_channel(src.data, &d, |p| &mut p.b, buf);
+    gaussian_channel(src.data, &d, |p| &mut p.a, buf);
 }
 
-fn gaussian_channel(data: &mut [u8], d: &BlurData, channel: usize, buf: &mut Vec<f64>) {
-    for i in 0..data.len() / 4 {
-        buf[i] = data[i * 4 + channel] as f64 / 255.0;
+fn gaussian_channel(
+    data: &mut [RGBA8],
+    d: &BlurData,
+    channel: fn(&mut RGBA8) -> &mut u8,
+    buf: &mut Vec<f64>,
+) {
+    for (p, v) in data.iter_mut().zip(buf.iter_mut()) {
+        *v = *channel(p) as f64 / 255.0;
     }
 
     gaussianiir2d(d, buf);
 
-    for i in 0..data.len() / 4 {
-        data[i * 4 + channel] = (buf[i] * 255.0) as u8;
+    for (p, v) in data.iter_mut().zip(buf.iter()) {
+        *channel(p) = (v * 255.0) as u8;
     }
 }
 
@@ -464,7 +464,7 @@ pub(crate) fn convert_group(
         (filters, filter_fill, filter_stroke)
     };
 
-    let transform: Transform = node.parse_attribute(AId::Transform).unwrap_or_default();
+    let transform = node.resolve_transform(state);
     let blend_mode: BlendMode = node.parse_attribute(AId::MixBlendMode).unwrap_or_default();
     let isolation: Isolation = node.parse_attribute(AId::Isolation).unwrap_or_default();
     let isolate = isolation == Isolation::Isolate;
@@ -482,14 +482,15 @@ pub(crate) fn convert_group(
         || force;
 
     if required {
-        let id = if is_g_or_use {
-            node.element_id().to_string()
+        let (id, classes) = if is_g_or_use {
+            (node.element_id().to_string(), node.classes())
         } else {
-            String::new()
+            (String::new(), Vec::new())
         };
 
         let g = parent.append_kind(NodeKind::Group(Group {
             id,
+            classes,
             transform,
             opacity,
             blend_mode,
@@ -611,7 +612,10 @@ fn convert_path(
 
     let has_bbox = path.has_bbox();
     let fill = crate::style::resolve_fill(node, has_bbox, state, cache);
-    let stroke = crate::style::resolve_stroke(node, has_bbox, state, cache);
+    let mut stroke = crate::style::resolve_stroke(node, has_bbox, state, cache);
+    if let Some(ref mut stroke) = stroke {
+        apply_path_length(node, &path, stroke);
+    }
     let mut visibility: Visibility = node
         .find_and_parse_attribute(AId::Visibility)
         .unwrap_or_default();
@@ -647,6 +651,8 @@ fn convert_path(
         paint_order,
         rendering_mode,
         text_bbox: None,
+        text_baseline: None,
+        missing_glyphs_bbox: None,
         data: path,
     }));
 
@@ -659,6 +665,23 @@ fn convert_path(
     }
 }
 
+// 'The pathLength attribute is used to calibrate the user agent's own distance-along-a-path
+// calculations with the one specified by the author. [...] This includes [...] stroke dashing.'
+fn apply_path_length(node: rosvgtree::Node, path: &PathData, stroke: &mut Stroke) {
+    let path_length = match node.parse_attribute::<f64>(AId::PathLength) {
+        Some(v) if v.is_finite() && v > 0.0 => v,
+        _ => return,
+    };
+
+    if let Some(ref mut dasharray) = stroke.dasharray {
+        let scale = path.length() / path_length;
+        for dash in dasharray.iter_mut() {
+            *dash *= scale;
+        }
+        stroke.dashoffset = (stroke.dashoffset as f64 * scale) as f32;
+    }
+}
+
 pub fn svg_paint_order_to_usvg(order: svgtypes::PaintOrder) -> PaintOrder {
     match (order.order[0], order.order[1]) {
         (svgtypes::PaintOrderKind::Stroke, _) => PaintOrder::StrokeAndFill,
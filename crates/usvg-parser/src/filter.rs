@@ -662,10 +662,18 @@ fn convert_flood(fe: rosvgtree::Node) -> Kind {
 
 fn convert_gaussian_blur(fe: rosvgtree::Node, primitives: &[Primitive]) -> Kind {
     let (std_dev_x, std_dev_y) = convert_std_dev_attr(fe, "0 0");
+    // Unlike `feConvolveMatrix`, the default is `none`.
+    let edge_mode = match fe.attribute(AId::EdgeMode).unwrap_or("none") {
+        "duplicate" => EdgeMode::Duplicate,
+        "wrap" => EdgeMode::Wrap,
+        _ => EdgeMode::None,
+    };
+
     Kind::GaussianBlur(GaussianBlur {
         input: resolve_input(fe, AId::In, primitives),
         std_dev_x,
         std_dev_y,
+        edge_mode,
     })
 }
 
@@ -1098,6 +1106,7 @@ fn convert_blur_function(node: rosvgtree::Node, std_dev: Length, state: &convert
         input: Input::SourceGraphic,
         std_dev_x: std_dev,
         std_dev_y: std_dev,
+        edge_mode: EdgeMode::None,
     })
 }
 
@@ -2,6 +2,7 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use rosvgtree::{self, AttributeId as AId};
@@ -53,21 +54,9 @@ impl ImageHrefResolver {
     /// Note that it will simply match the `mime` or data's magic.
     /// The actual images would not be decoded. It's up to the renderer.
     pub fn default_data_resolver() -> ImageHrefDataResolverFn {
-        Box::new(
-            move |mime: &str, data: Arc<Vec<u8>>, opts: &Options| match mime {
-                "image/jpg" | "image/jpeg" => Some(ImageKind::JPEG(data)),
-                "image/png" => Some(ImageKind::PNG(data)),
-                "image/gif" => Some(ImageKind::GIF(data)),
-                "image/svg+xml" => load_sub_svg(&data, opts),
-                "text/plain" => match get_image_data_format(&data) {
-                    Some(ImageFormat::JPEG) => Some(ImageKind::JPEG(data)),
-                    Some(ImageFormat::PNG) => Some(ImageKind::PNG(data)),
-                    Some(ImageFormat::GIF) => Some(ImageKind::GIF(data)),
-                    _ => load_sub_svg(&data, opts),
-                },
-                _ => None,
-            },
-        )
+        Box::new(move |mime: &str, data: Arc<Vec<u8>>, opts: &Options| {
+            resolve_data(mime, data, opts, &Nesting::default())
+        })
     }
 
     /// Creates a default string resolver.
@@ -94,7 +83,7 @@ impl ImageHrefResolver {
                     Some(ImageFormat::JPEG) => Some(ImageKind::JPEG(Arc::new(data))),
                     Some(ImageFormat::PNG) => Some(ImageKind::PNG(Arc::new(data))),
                     Some(ImageFormat::GIF) => Some(ImageKind::GIF(Arc::new(data))),
-                    Some(ImageFormat::SVG) => load_sub_svg(&data, opts),
+                    Some(ImageFormat::SVG) => load_sub_svg(&data, opts, &Nesting::default()),
                     _ => {
                         log::warn!("'{}' is not a PNG, JPEG, GIF or SVG(Z) image.", href);
                         None
@@ -106,6 +95,23 @@ impl ImageHrefResolver {
             }
         })
     }
+
+    /// Creates a string resolver that loads images using the provided function.
+    ///
+    /// `fetch` would be called with an `xlink:href` value and should return image's data.
+    /// Can be used to load images from a network or an archive, since usvg
+    /// doesn't do any I/O besides reading local files.
+    ///
+    /// The image type is detected using data's magic. Anything else is treated as SVG.
+    pub fn string_resolver_from_fn<F>(fetch: F) -> ImageHrefStringResolverFn
+    where
+        F: Fn(&str) -> Option<Vec<u8>> + Send + Sync + 'static,
+    {
+        Box::new(move |href: &str, opts: &Options| {
+            let data = fetch(href)?;
+            resolve_untyped_data(Arc::new(data), opts, &Nesting::default())
+        })
+    }
 }
 
 impl std::fmt::Debug for ImageHrefResolver {
@@ -214,11 +220,69 @@ fn get_image_data_format(data: &[u8]) -> Option<ImageFormat> {
     }
 }
 
-/// Tries to load the `ImageData` content as an SVG image.
+/// The maximum nesting level of SVG images.
 ///
-/// Unlike `Tree::from_*` methods, this one will also remove all `image` elements
-/// from the loaded SVG, as required by the spec.
-pub(crate) fn load_sub_svg(data: &[u8], opt: &Options) -> Option<ImageKind> {
+/// Each level is parsed recursively, so this limits the stack usage.
+const MAX_SUB_SVG_DEPTH: u32 = 8;
+
+/// The maximum number of SVG images loaded by a single top-level SVG image,
+/// including all nested ones.
+///
+/// An SVG image can reference the same nested image multiple times, e.g. via `use`,
+/// and each reference is parsed again. Without a limit, the loading time would grow
+/// exponentially with the nesting depth.
+const MAX_SUB_SVG_LOADS: u32 = 100;
+
+/// The state of nested SVG images loading.
+#[derive(Clone, Default)]
+struct Nesting {
+    /// The nesting level of the SVG that references an image.
+    depth: u32,
+    /// The number of SVG images loaded so far, shared by all nesting levels.
+    loads: Arc<AtomicU32>,
+}
+
+fn resolve_data(
+    mime: &str,
+    data: Arc<Vec<u8>>,
+    opts: &Options,
+    nesting: &Nesting,
+) -> Option<ImageKind> {
+    match mime {
+        "image/jpg" | "image/jpeg" => Some(ImageKind::JPEG(data)),
+        "image/png" => Some(ImageKind::PNG(data)),
+        "image/gif" => Some(ImageKind::GIF(data)),
+        "image/svg+xml" => load_sub_svg(&data, opts, nesting),
+        "text/plain" => resolve_untyped_data(data, opts, nesting),
+        _ => None,
+    }
+}
+
+fn resolve_untyped_data(
+    data: Arc<Vec<u8>>,
+    opts: &Options,
+    nesting: &Nesting,
+) -> Option<ImageKind> {
+    match get_image_data_format(&data) {
+        Some(ImageFormat::JPEG) => Some(ImageKind::JPEG(data)),
+        Some(ImageFormat::PNG) => Some(ImageKind::PNG(data)),
+        Some(ImageFormat::GIF) => Some(ImageKind::GIF(data)),
+        _ => load_sub_svg(&data, opts, nesting),
+    }
+}
+
+/// Loads an SVG image.
+fn load_sub_svg(data: &[u8], opt: &Options, nesting: &Nesting) -> Option<ImageKind> {
+    if nesting.depth >= MAX_SUB_SVG_DEPTH {
+        log::warn!("SVG images nesting is too deep. Skipped.");
+        return None;
+    }
+
+    if nesting.loads.fetch_add(1, Ordering::Relaxed) >= MAX_SUB_SVG_LOADS {
+        log::warn!("Too many nested SVG images. Skipped.");
+        return None;
+    }
+
     let mut sub_opt = Options::default();
     sub_opt.resources_dir = None;
     sub_opt.dpi = opt.dpi;
@@ -229,6 +293,19 @@ pub(crate) fn load_sub_svg(data: &[u8], opt: &Options) -> Option<ImageKind> {
     sub_opt.image_rendering = opt.image_rendering;
     sub_opt.default_size = opt.default_size;
 
+    // Like in browsers, the referenced SVG image cannot load external resources.
+    // Only embedded images are allowed, including other SVG images.
+    let nesting = Nesting {
+        depth: nesting.depth + 1,
+        loads: nesting.loads.clone(),
+    };
+    sub_opt.image_href_resolver = ImageHrefResolver {
+        resolve_data: Box::new(move |mime: &str, data: Arc<Vec<u8>>, opts: &Options| {
+            resolve_data(mime, data, opts, &nesting)
+        }),
+        resolve_string: Box::new(|_, _| None),
+    };
+
     let tree = match Tree::from_data(data, &sub_opt) {
         Ok(tree) => tree,
         Err(_) => {
@@ -237,34 +314,5 @@ pub(crate) fn load_sub_svg(data: &[u8], opt: &Options) -> Option<ImageKind> {
         }
     };
 
-    sanitize_sub_svg(&tree);
     Some(ImageKind::SVG(tree))
 }
-
-// TODO: technically can simply override Options::image_href_resolver?
-fn sanitize_sub_svg(tree: &Tree) {
-    // Remove all Image nodes.
-    //
-    // The referenced SVG image cannot have any 'image' elements by itself.
-    // Not only recursive. Any. Don't know why.
-
-    // TODO: implement drain or something to the rctree.
-    let mut changed = true;
-    while changed {
-        changed = false;
-
-        for node in tree.root.descendants() {
-            let mut rm = false;
-            // TODO: feImage?
-            if let NodeKind::Image(_) = *node.borrow() {
-                rm = true;
-            };
-
-            if rm {
-                node.detach();
-                changed = true;
-                break;
-            }
-        }
-    }
-}
@@ -74,6 +74,7 @@ enum MarkerKind {
 
 enum MarkerOrientation {
     Auto,
+    AutoStartReverse,
     Angle(f64),
 }
 
@@ -132,6 +133,11 @@ fn resolve(
 
         let angle = match convert_orientation(marker_node) {
             MarkerOrientation::Auto => calc_vertex_angle(&segments, idx),
+            // Only the start marker is reversed. Mainly used to draw arrows on both ends.
+            MarkerOrientation::AutoStartReverse => match marker_kind {
+                MarkerKind::Start => calc_vertex_angle(&segments, idx) + 180.0,
+                _ => calc_vertex_angle(&segments, idx),
+            },
             MarkerOrientation::Angle(angle) => angle,
         };
 
@@ -462,12 +468,12 @@ fn convert_rect(node: rosvgtree::Node, state: &converter::State) -> Option<Rect>
 }
 
 fn convert_orientation(node: rosvgtree::Node) -> MarkerOrientation {
-    if node.attribute(AId::Orient) == Some("auto") {
-        MarkerOrientation::Auto
-    } else {
-        match node.parse_attribute::<svgtypes::Angle>(AId::Orient) {
+    match node.attribute(AId::Orient) {
+        Some("auto") => MarkerOrientation::Auto,
+        Some("auto-start-reverse") => MarkerOrientation::AutoStartReverse,
+        _ => match node.parse_attribute::<svgtypes::Angle>(AId::Orient) {
             Some(angle) => MarkerOrientation::Angle(angle.to_degrees()),
             None => MarkerOrientation::Angle(0.0),
-        }
+        },
     }
 }
@@ -42,6 +42,10 @@ pub struct Options {
     ///
     /// Will be used to resolve a `systemLanguage` conditional attribute.
     ///
+    /// `switch` elements are resolved during parsing, therefore the tree
+    /// retains only the selected child. To render another language variant,
+    /// the SVG has to be parsed again.
+    ///
     /// Format: en, en-US.
     ///
     /// Default: `[en]`
@@ -73,6 +73,7 @@ fn convert_linear(node: rosvgtree::Node, state: &converter::State) -> Option<Ser
             units,
             transform,
             spread_method: convert_spread_method(node),
+            color_interpolation: convert_color_interpolation(node),
             stops,
         },
     };
@@ -136,6 +137,7 @@ fn convert_radial(node: rosvgtree::Node, state: &converter::State) -> Option<Ser
             units,
             transform,
             spread_method,
+            color_interpolation: convert_color_interpolation(node),
             stops,
         },
     };
@@ -208,6 +210,11 @@ fn convert_spread_method(node: rosvgtree::Node) -> SpreadMethod {
     node.parse_attribute(AId::SpreadMethod).unwrap_or_default()
 }
 
+fn convert_color_interpolation(node: rosvgtree::Node) -> filter::ColorInterpolation {
+    node.find_and_parse_attribute(AId::ColorInterpolation)
+        .unwrap_or(filter::ColorInterpolation::SRGB)
+}
+
 pub(crate) fn convert_units(node: rosvgtree::Node, name: AId, def: Units) -> Units {
     let node = resolve_attr(node, name);
     node.parse_attribute(name).unwrap_or(def)
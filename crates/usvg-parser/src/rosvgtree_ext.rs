@@ -260,6 +260,8 @@ pub trait SvgNodeExt {
         state: &converter::State,
         def: svgtypes::Length,
     ) -> f64;
+    fn resolve_transform(&self, state: &converter::State) -> Transform;
+    fn classes(&self) -> Vec<String>;
     fn is_visible_element(&self, opt: &crate::Options) -> bool;
 }
 
@@ -358,6 +360,39 @@ impl SvgNodeExt for Node<'_, '_> {
         self.convert_length(aid, Units::UserSpaceOnUse, state, def)
     }
 
+    fn classes(&self) -> Vec<String> {
+        self.attribute(AttributeId::Class)
+            .map(|v| v.split_whitespace().map(|s| s.to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    fn resolve_transform(&self, state: &converter::State) -> Transform {
+        let ts: Transform = self
+            .parse_attribute(AttributeId::Transform)
+            .unwrap_or_default();
+        if ts.is_default() {
+            return ts;
+        }
+
+        let (x, y) = match self
+            .attribute(AttributeId::TransformOrigin)
+            .and_then(parse_transform_origin)
+        {
+            Some(v) => v,
+            None => return ts,
+        };
+
+        // Percentages are relative to the viewport, since SVG elements
+        // use `transform-box: view-box` by default.
+        let x = units::convert_length(x, *self, AttributeId::Width, Units::UserSpaceOnUse, state);
+        let y = units::convert_length(y, *self, AttributeId::Height, Units::UserSpaceOnUse, state);
+
+        let mut origin_ts = Transform::new_translate(x, y);
+        origin_ts.append(&ts);
+        origin_ts.translate(-x, -y);
+        origin_ts
+    }
+
     fn is_visible_element(&self, opt: &crate::Options) -> bool {
         self.attribute(AttributeId::Display) != Some("none")
             && self.has_valid_transform(AttributeId::Transform)
@@ -401,3 +436,45 @@ impl SvgColorExt for svgtypes::Color {
         )
     }
 }
+
+/// Parses a two-dimensional `transform-origin` value.
+///
+/// The `z` component is ignored.
+fn parse_transform_origin(value: &str) -> Option<(svgtypes::Length, svgtypes::Length)> {
+    use svgtypes::{Length, LengthUnit};
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Axis {
+        X,
+        Y,
+        Any,
+    }
+
+    fn parse_item(text: &str) -> Option<(Length, Axis)> {
+        let percent = |n| Length::new(n, LengthUnit::Percent);
+        match text {
+            "left" => Some((percent(0.0), Axis::X)),
+            "right" => Some((percent(100.0), Axis::X)),
+            "top" => Some((percent(0.0), Axis::Y)),
+            "bottom" => Some((percent(100.0), Axis::Y)),
+            "center" => Some((percent(50.0), Axis::Any)),
+            _ => Length::from_str(text).ok().map(|l| (l, Axis::Any)),
+        }
+    }
+
+    let mut items = value.split_whitespace();
+    let center = Length::new(50.0, LengthUnit::Percent);
+    let first = parse_item(items.next()?)?;
+    let second = match items.next() {
+        Some(text) => Some(parse_item(text)?),
+        None => None,
+    };
+
+    match second {
+        None if first.1 == Axis::Y => Some((center, first.0)),
+        None => Some((first.0, center)),
+        // Keywords can be in any order.
+        Some(second) if first.1 == Axis::Y || second.1 == Axis::X => Some((second.0, first.0)),
+        Some(second) => Some((first.0, second.0)),
+    }
+}
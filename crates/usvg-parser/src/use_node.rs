@@ -29,7 +29,7 @@ pub(crate) fn convert(
     }
 
     // We require an original transformation to setup 'clipPath'.
-    let mut orig_ts: Transform = node.parse_attribute(AId::Transform).unwrap_or_default();
+    let mut orig_ts = node.resolve_transform(state);
     let mut new_ts = Transform::default();
 
     {
@@ -133,7 +133,7 @@ pub(crate) fn convert_svg(
     parent: &mut Node,
 ) {
     // We require original transformation to setup 'clipPath'.
-    let mut orig_ts: Transform = node.parse_attribute(AId::Transform).unwrap_or_default();
+    let mut orig_ts = node.resolve_transform(state);
     let mut new_ts = Transform::default();
 
     {
@@ -215,6 +215,7 @@ fn clip_element(
 
     parent.append_kind(NodeKind::Group(Group {
         id: node.element_id().to_string(),
+        classes: node.classes(),
         transform,
         clip_path: Some(Rc::new(clip_path)),
         ..Group::default()
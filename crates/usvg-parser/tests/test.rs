@@ -1,5 +1,6 @@
 use usvg_parser::TreeParsing;
 use usvg_tree::FuzzyEq;
+use usvg_tree::NodeExt;
 
 #[test]
 fn clippath_with_invalid_child() {
@@ -97,3 +98,223 @@ fn invalid_size_1() {
     let result = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default());
     assert!(result.is_err());
 }
+
+#[test]
+fn group_needs_layer() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 1 1'>
+        <g opacity='0.5' id='g1'>
+            <rect width='10' height='10'/>
+        </g>
+        <g style='mix-blend-mode:multiply' id='g2'>
+            <rect width='10' height='10'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+
+    let needs_layer = |id: &str| match *tree.node_by_id(id).unwrap().borrow() {
+        usvg_tree::NodeKind::Group(ref g) => g.needs_layer(),
+        _ => unreachable!(),
+    };
+
+    assert_eq!(needs_layer("g1"), true);
+    assert_eq!(needs_layer("g2"), true);
+
+    let mut group = usvg_tree::Group::default();
+    group.transform = usvg_tree::Transform::new_translate(1.0, 1.0);
+    assert_eq!(group.needs_layer(), false);
+}
+
+#[test]
+fn image_string_resolver_from_fn() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink'>
+        <image id='image1' width='10' height='10' xlink:href='https://example.com/image.png'/>
+        <image id='image2' width='10' height='10' xlink:href='https://example.com/missing.png'/>
+    </svg>
+    ";
+
+    let mut opt = usvg_parser::Options::default();
+    opt.image_href_resolver.resolve_string =
+        usvg_parser::ImageHrefResolver::string_resolver_from_fn(|href| match href {
+            "https://example.com/image.png" => {
+                Some(include_bytes!("../../resvg/tests/resources/image.png").to_vec())
+            }
+            _ => None,
+        });
+
+    let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+
+    let is_png = match *tree.node_by_id("image1").unwrap().borrow() {
+        usvg_tree::NodeKind::Image(ref image) => {
+            matches!(image.kind, usvg_tree::ImageKind::PNG(_))
+        }
+        _ => false,
+    };
+    assert!(is_png);
+    assert!(tree.node_by_id("image2").is_none());
+}
+
+#[test]
+fn stroke_dasharray_with_path_length() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <path id='path1' d='M 0 0 L 100 0' pathLength='50' stroke='black'
+              stroke-dasharray='5 10' stroke-dashoffset='2'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let (dasharray, dashoffset) = match *tree.node_by_id("path1").unwrap().borrow() {
+        usvg_tree::NodeKind::Path(ref path) => {
+            let stroke = path.stroke.as_ref().unwrap();
+            (stroke.dasharray.clone().unwrap(), stroke.dashoffset)
+        }
+        _ => unreachable!(),
+    };
+
+    assert_eq!(dasharray.len(), 2);
+    assert!((dasharray[0] - 10.0).abs() < 1e-6);
+    assert!((dasharray[1] - 20.0).abs() < 1e-6);
+    assert!((dashoffset - 4.0).abs() < 1e-6);
+}
+
+#[test]
+fn nested_functions_in_style() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 100 100'>
+        <rect id='rect1' width='10' height='10' fill='red'
+              style='filter:drop-shadow(1px 1px rgba(0, 0, 0, 0.5)) blur(2px); fill:green'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    match *tree.root.first_child().unwrap().borrow() {
+        usvg_tree::NodeKind::Group(ref group) => assert_eq!(group.filters.len(), 2),
+        _ => unreachable!(),
+    };
+
+    match *tree.node_by_id("rect1").unwrap().borrow() {
+        usvg_tree::NodeKind::Path(ref path) => {
+            let fill = path.fill.as_ref().unwrap();
+            assert_eq!(
+                fill.paint,
+                usvg_tree::Paint::Color(usvg_tree::Color::new_rgb(0, 128, 0))
+            );
+        }
+        _ => unreachable!(),
+    };
+}
+
+#[test]
+fn transform_origin() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 200 100'>
+        <rect id='rect1' width='10' height='10' transform='scale(2)' transform-origin='right 10'/>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let ts = tree.root.first_child().unwrap().transform();
+    // The origin stays in place.
+    assert!(ts.fuzzy_eq(&usvg_tree::Transform::new(
+        2.0, 0.0, 0.0, 2.0, -200.0, -10.0
+    )));
+}
+
+#[test]
+fn group_classes() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <g class=' layer  print '>
+            <rect width='10' height='10'/>
+        </g>
+    </svg>
+    ";
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let node = tree.root.first_child().unwrap();
+    match *node.borrow() {
+        usvg_tree::NodeKind::Group(ref g) => assert_eq!(g.classes, vec!["layer", "print"]),
+        _ => unreachable!(),
+    };
+}
+
+#[test]
+fn switch_languages() {
+    let svg = "
+    <svg xmlns='http://www.w3.org/2000/svg'>
+        <switch>
+            <rect id='ru' systemLanguage='ru' width='10' height='10'/>
+            <rect id='en' systemLanguage='en' width='10' height='10'/>
+        </switch>
+    </svg>
+    ";
+
+    let selected = |languages: &[&str]| {
+        let opt = usvg_parser::Options {
+            languages: languages.iter().map(|s| s.to_string()).collect(),
+            ..usvg_parser::Options::default()
+        };
+        let tree = usvg_tree::Tree::from_str(svg, &opt).unwrap();
+        tree.root
+            .descendants()
+            .map(|n| n.id().to_string())
+            .filter(|id| !id.is_empty())
+            .collect::<Vec<_>>()
+    };
+
+    // Only the selected child is retained.
+    assert_eq!(selected(&["en"]), vec!["en"]);
+    assert_eq!(selected(&["ru", "en"]), vec!["ru"]);
+}
+
+#[test]
+fn nested_svg_images_are_limited() {
+    fn to_data_url(svg: &str) -> String {
+        let mut url = String::from("data:image/svg+xml,");
+        for b in svg.bytes() {
+            if b.is_ascii_alphanumeric() {
+                url.push(b as char);
+            } else {
+                url.push_str(&format!("%{:02X}", b));
+            }
+        }
+        url
+    }
+
+    fn count_images(root: &usvg_tree::Node) -> usize {
+        root.descendants()
+            .map(|node| match *node.borrow() {
+                usvg_tree::NodeKind::Image(usvg_tree::Image {
+                    kind: usvg_tree::ImageKind::SVG(ref tree),
+                    ..
+                }) => 1 + count_images(&tree.root),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    // Every level references the previous one 4 times,
+    // which would be 4^1 + ... + 4^6 = 5460 images without a limit.
+    let mut svg =
+        "<svg xmlns='http://www.w3.org/2000/svg' width='1' height='1'><rect/></svg>".to_string();
+    for _ in 0..6 {
+        svg = format!(
+            "<svg xmlns='http://www.w3.org/2000/svg' xmlns:xlink='http://www.w3.org/1999/xlink' \
+                  width='1' height='1'>\
+                <defs><image id='image1' width='1' height='1' xlink:href='{}'/></defs>\
+                <use xlink:href='#image1'/><use xlink:href='#image1'/>\
+                <use xlink:href='#image1'/><use xlink:href='#image1'/>\
+            </svg>",
+            to_data_url(&svg)
+        );
+    }
+
+    let tree = usvg_tree::Tree::from_str(&svg, &usvg_parser::Options::default()).unwrap();
+    let count = count_images(&tree.root);
+    // Each top-level image loads up to 100 images, including itself.
+    assert!(count > 4 && count <= 4 * 100, "{}", count);
+}
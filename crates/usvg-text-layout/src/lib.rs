@@ -571,7 +571,14 @@ fn text_to_paths(
                 new_paths.push(path);
             }
 
-            if let Some(path) = convert_span(span, &mut clusters, &span_ts) {
+            if let Some(mut path) = convert_span(span, &mut clusters, &span_ts) {
+                // Baseline snapping makes sense only for a horizontal, linear text.
+                let is_horizontal = text_node.writing_mode == WritingMode::LeftToRight
+                    && matches!(chunk.text_flow, TextFlow::Linear);
+                if !is_horizontal {
+                    path.text_baseline = None;
+                }
+
                 // Use `text_bbox` here and not `path.data.bbox()`.
                 if let Some(r) = path.text_bbox {
                     bbox = bbox.expand(r.to_path_bbox());
@@ -668,6 +675,8 @@ fn convert_span(
 ) -> Option<Path> {
     let mut path_data = PathData::new();
     let mut bboxes_data = PathData::new();
+    let mut missing_bboxes_data = PathData::new();
+    let mut baseline = None;
 
     for cluster in clusters {
         if !cluster.visible {
@@ -675,6 +684,11 @@ fn convert_span(
         }
 
         if span_contains(span, cluster.byte_idx) {
+            if baseline.is_none() {
+                let (x, y) = cluster.transform.apply(0.0, 0.0);
+                baseline = Some(text_ts.apply(x, y).1);
+            }
+
             let mut path = std::mem::replace(&mut cluster.path, PathData::new());
             path.transform(cluster.transform);
 
@@ -684,17 +698,23 @@ fn convert_span(
             if let Some(r) = Rect::new(0.0, -cluster.ascent, cluster.advance, cluster.height()) {
                 if let Some(r) = r.transform(&cluster.transform) {
                     bboxes_data.push_rect(r);
+
+                    if cluster.has_missing_glyphs {
+                        missing_bboxes_data.push_rect(r);
+                    }
                 }
             }
         }
     }
 
-    if path_data.is_empty() {
+    // Keep spans consisting of missing glyphs only, so they can be highlighted.
+    if path_data.is_empty() && missing_bboxes_data.is_empty() {
         return None;
     }
 
     path_data.transform(*text_ts);
     bboxes_data.transform(*text_ts);
+    missing_bboxes_data.transform(*text_ts);
 
     let mut fill = span.fill.clone();
     if let Some(ref mut fill) = fill {
@@ -715,6 +735,8 @@ fn convert_span(
         paint_order: span.paint_order,
         rendering_mode: ShapeRendering::default(),
         text_bbox: bboxes_data.bbox().and_then(|r| r.to_rect()),
+        text_baseline: baseline,
+        missing_glyphs_bbox: missing_bboxes_data.bbox().and_then(|r| r.to_rect()),
         data: Rc::new(path_data),
     };
 
@@ -843,6 +865,7 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: PathBbox) -> Option<Pai
                     units: Units::UserSpaceOnUse,
                     transform,
                     spread_method: lg.spread_method,
+                    color_interpolation: lg.color_interpolation,
                     stops: lg.stops.clone(),
                 },
             }))
@@ -861,6 +884,7 @@ fn paint_server_to_user_space_on_use(paint: Paint, bbox: PathBbox) -> Option<Pai
                     units: Units::UserSpaceOnUse,
                     transform,
                     spread_method: rg.spread_method,
+                    color_interpolation: rg.color_interpolation,
                     stops: rg.stops.clone(),
                 },
             }))
@@ -973,6 +997,9 @@ struct OutlinedCluster {
     /// Used during the `text-decoration` processing.
     has_relative_shift: bool,
 
+    /// Indicates that this cluster contains glyphs that are missing in all fonts.
+    has_missing_glyphs: bool,
+
     /// An actual outline.
     path: PathData,
 
@@ -1310,6 +1337,7 @@ fn outline_cluster(
         descent: font.descent(font_size),
         x_height: font.x_height(font_size),
         has_relative_shift: false,
+        has_missing_glyphs: glyphs.iter().any(|g| g.is_missing()),
         path,
         transform: Transform::default(),
         visible: true,
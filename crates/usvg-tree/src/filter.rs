@@ -502,6 +502,11 @@ pub struct GaussianBlur {
     ///
     /// `stdDeviation` in the SVG.
     pub std_dev_y: PositiveF64,
+
+    /// An edges processing mode.
+    ///
+    /// `edgeMode` in the SVG.
+    pub edge_mode: EdgeMode,
 }
 
 /// An image filter primitive.
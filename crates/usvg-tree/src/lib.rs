@@ -253,6 +253,11 @@ pub struct BaseGradient {
     /// `spreadMethod` in SVG.
     pub spread_method: SpreadMethod,
 
+    /// A color space in which stop colors are interpolated.
+    ///
+    /// `color-interpolation` in SVG. Unlike in filters, defaults to sRGB.
+    pub color_interpolation: filter::ColorInterpolation,
+
     /// A list of `stop` elements.
     pub stops: Vec<Stop>,
 }
@@ -759,6 +764,12 @@ pub struct Group {
     /// Can be empty.
     pub id: String,
 
+    /// Element's classes.
+    ///
+    /// Taken from the `class` attribute.
+    /// Set only for `g` and `use` elements.
+    pub classes: Vec<String>,
+
     /// Element transform.
     pub transform: Transform,
 
@@ -802,6 +813,7 @@ impl Default for Group {
     fn default() -> Self {
         Group {
             id: String::new(),
+            classes: Vec::new(),
             transform: Transform::default(),
             opacity: Opacity::ONE,
             blend_mode: BlendMode::Normal,
@@ -825,6 +837,21 @@ impl Group {
             || !self.filters.is_empty()
             || self.blend_mode != BlendMode::Normal // TODO: probably not needed?
     }
+
+    /// Checks if this group has to be rendered onto a separate layer.
+    ///
+    /// Returns `true` when the group has filters, a mask, a clip path,
+    /// an opacity less than one, a non-normal blend mode or isolation.
+    /// Otherwise, the group affects only the transform of its children
+    /// and `resvg` renders them directly onto the parent canvas.
+    pub fn needs_layer(&self) -> bool {
+        !self.filters.is_empty()
+            || self.mask.is_some()
+            || self.clip_path.is_some()
+            || self.opacity != Opacity::ONE
+            || self.blend_mode != BlendMode::Normal
+            || self.isolate
+    }
 }
 
 /// Representation of the [`paint-order`] property.
@@ -893,6 +920,24 @@ pub struct Path {
     /// that were converted from text.
     pub text_bbox: Option<Rect>,
 
+    /// Contains a text baseline position.
+    ///
+    /// The `y` coordinate of the first glyph's baseline in path coordinates.
+    /// Can be used by renderers to align text to a pixel grid.
+    ///
+    /// Will be set only for paths that were converted from a horizontal text
+    /// that is not placed on a path.
+    pub text_baseline: Option<f64>,
+
+    /// Contains a bbox of glyphs that are missing in all fonts.
+    ///
+    /// Such glyphs are outlined using the `.notdef` glyph, which is usually
+    /// a box or nothing at all. Like the text bbox, it's based on the font metrics.
+    ///
+    /// Will be set only for paths that were converted from text.
+    /// The path can be empty when all of its glyphs are missing.
+    pub missing_glyphs_bbox: Option<Rect>,
+
     /// Segments list.
     ///
     /// All segments are in absolute coordinates.
@@ -910,6 +955,8 @@ impl Default for Path {
             paint_order: PaintOrder::default(),
             rendering_mode: ShapeRendering::default(),
             text_bbox: None,
+            text_baseline: None,
+            missing_glyphs_bbox: None,
             data: Rc::new(PathData::default()),
         }
     }
@@ -1258,6 +1305,18 @@ pub trait NodeExt {
     /// without converting it into paths first.
     fn calculate_bbox(&self) -> Option<PathBbox>;
 
+    /// Calculates node's bounding box in its own coordinates.
+    ///
+    /// Unlike [`calculate_bbox`](NodeExt::calculate_bbox), transforms of the node
+    /// and its ancestors are not applied. Which allows mapping the bounding box
+    /// into any coordinate system without losing its orientation.
+    ///
+    /// Can be expensive on large paths and groups.
+    ///
+    /// Always returns `None` for `NodeKind::Text` since we cannot calculate its bbox
+    /// without converting it into paths first.
+    fn calculate_local_bbox(&self) -> Option<PathBbox>;
+
     /// Calls a closure for each subroot this `Node` has.
     ///
     /// The [`Tree::root`](Tree::root) field contain only render-able SVG elements.
@@ -1320,6 +1379,11 @@ impl NodeExt for Node {
         calc_node_bbox(self, self.abs_transform())
     }
 
+    #[inline]
+    fn calculate_local_bbox(&self) -> Option<PathBbox> {
+        calc_node_bbox(self, Transform::default())
+    }
+
     fn subroots<F: FnMut(Node)>(&self, mut f: F) {
         node_subroots(self, &mut f)
     }
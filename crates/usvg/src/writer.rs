@@ -136,6 +136,10 @@ fn conv_filters(tree: &Tree, opt: &XmlOptions, xml: &mut XmlWriter) {
                         AId::StdDeviation.to_str(),
                         format_args!("{} {}", blur.std_dev_x.get(), blur.std_dev_y.get()),
                     );
+                    // `none` is the default for `feGaussianBlur`.
+                    if blur.edge_mode != filter::EdgeMode::None {
+                        xml.write_edge_mode(blur.edge_mode);
+                    }
                     xml.write_svg_attribute(AId::Result, &fe.result);
                     xml.end_element();
                 }
@@ -314,14 +318,7 @@ fn conv_filters(tree: &Tree, opt: &XmlOptions, xml: &mut XmlWriter) {
                     xml.write_svg_attribute(AId::Bias, &matrix.bias);
                     xml.write_svg_attribute(AId::TargetX, &matrix.matrix.target_x);
                     xml.write_svg_attribute(AId::TargetY, &matrix.matrix.target_y);
-                    xml.write_svg_attribute(
-                        AId::EdgeMode,
-                        match matrix.edge_mode {
-                            filter::EdgeMode::None => "none",
-                            filter::EdgeMode::Duplicate => "duplicate",
-                            filter::EdgeMode::Wrap => "wrap",
-                        },
-                    );
+                    xml.write_edge_mode(matrix.edge_mode);
                     xml.write_svg_attribute(
                         AId::PreserveAlpha,
                         if matrix.preserve_alpha {
@@ -689,6 +686,7 @@ trait XmlWriterExt {
     fn write_point<T: Display>(&mut self, id: AId, p: Point<T>);
     fn write_image_data(&mut self, kind: &ImageKind);
     fn write_filter_input(&mut self, id: AId, input: &filter::Input);
+    fn write_edge_mode(&mut self, mode: filter::EdgeMode);
     fn write_filter_primitive_attrs(&mut self, fe: &filter::Primitive);
     fn write_filter_transfer_function(&mut self, eid: EId, fe: &filter::TransferFunction);
 }
@@ -843,6 +841,17 @@ impl XmlWriterExt for XmlWriter {
         self.write_attribute_fmt(id.to_str(), format_args!("{} {}", p.x, p.y));
     }
 
+    fn write_edge_mode(&mut self, mode: filter::EdgeMode) {
+        self.write_svg_attribute(
+            AId::EdgeMode,
+            match mode {
+                filter::EdgeMode::None => "none",
+                filter::EdgeMode::Duplicate => "duplicate",
+                filter::EdgeMode::Wrap => "wrap",
+            },
+        );
+    }
+
     fn write_filter_input(&mut self, id: AId, input: &filter::Input) {
         self.write_attribute(
             id.to_str(),
@@ -975,6 +984,10 @@ fn write_base_grad(g: &BaseGradient, xml: &mut XmlWriter, opt: &XmlOptions) {
         SpreadMethod::Repeat => xml.write_svg_attribute(AId::SpreadMethod, "repeat"),
     }
 
+    if g.color_interpolation == filter::ColorInterpolation::LinearRGB {
+        xml.write_svg_attribute(AId::ColorInterpolation, "linearRGB");
+    }
+
     for s in &g.stops {
         xml.start_svg_element(EId::Stop);
         xml.write_svg_attribute(AId::Offset, &s.offset.get());